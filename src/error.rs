@@ -0,0 +1,59 @@
+use crate::ui::fonts::FontError;
+use crate::ui::windows::window_theme::ThemeError;
+use std::fmt::Display;
+
+/// Everything that can go wrong in creeperUI, returned by the `try_` functions.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    Font(FontError),
+    Theme(ThemeError),
+    /// An image that couldn't be loaded.
+    Texture {
+        path: String,
+        message: String,
+    },
+    /// The system clipboard couldn't be opened, read or written.
+    Clipboard(String),
+    /// Two widgets of the same type with the same label and ID in one window and frame.
+    DuplicateId {
+        widget_type: String,
+        label: String,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Font(e) => write!(f, "{e}"),
+            Error::Theme(e) => write!(f, "{e}"),
+            Error::Texture { path, message } => write!(f, "couldn't load image {path}: {message}"),
+            Error::Clipboard(message) => write!(f, "clipboard error: {message}"),
+            Error::DuplicateId { widget_type, label } => write!(
+                f,
+                "a {widget_type} widget labeled `{label}` already exists. Please give it a unique explicit ID."
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Font(e) => Some(e),
+            Error::Theme(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FontError> for Error {
+    fn from(e: FontError) -> Self {
+        Error::Font(e)
+    }
+}
+
+impl From<ThemeError> for Error {
+    fn from(e: ThemeError) -> Self {
+        Error::Theme(e)
+    }
+}
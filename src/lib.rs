@@ -2,13 +2,21 @@
 
 pub mod ui;
 pub mod widgets;
+mod error;
 mod misc;
 
+pub use error::Error;
+
+pub use ui::draw_list::{DrawCommand, DrawList};
+pub use ui::focus::{FocusState, NavDirection, NavInput};
+pub use ui::fonts::{FontError, FontSet, FontSource, FontStyle, UiFont};
+pub use ui::input::{GamepadButton, InputSource, MacroquadInput, ScriptedInput, UiInput};
 pub use ui::ui::UI;
 pub use ui::windows::action_type::ActionType;
 pub use ui::windows::window::Window;
 pub use ui::windows::window_handler::{WindowHandler, WindowId};
 pub use ui::windows::window_properties::WindowProperties;
-pub use ui::windows::window_theme::WindowTheme;
+pub use ui::windows::style::StyleVar;
+pub use ui::windows::window_theme::{ThemeColor, ThemeError, ThemePreset, WindowTheme};
 
 pub use widgets::*;
\ No newline at end of file
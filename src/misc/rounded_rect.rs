@@ -34,6 +34,7 @@ pub fn draw_rounded_rect(x: f32, y: f32, w: f32, h: f32, radius: f32, color: Col
 /// r_tr = top-right radius
 /// r_bl = bottom-left radius
 /// r_br = bottom-right radius
+#[allow(clippy::too_many_arguments)]
 pub fn draw_rounded_rect_ex(
     x: f32,
     y: f32,
@@ -131,6 +132,7 @@ pub fn draw_rounded_rect_ex(
 /// Draw an outlined (stroke) rounded rectangle by painting an outer rounded rect (stroke color)
 /// then painting a smaller inner rounded rect with the `background` color to create the stroke.
 /// Thickness will be clamped so inner rect still exists.
+#[allow(clippy::too_many_arguments)]
 pub fn draw_rounded_rect_stroke(
     x: f32,
     y: f32,
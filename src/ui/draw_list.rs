@@ -0,0 +1,484 @@
+use crate::misc::rounded_rect::{draw_rounded_rect_ex, draw_rounded_rect_stroke};
+use crate::ui::fonts::FontSet;
+use macroquad::prelude::*;
+
+/// A single primitive emitted by a window or widget.
+#[derive(Clone, Debug)]
+pub enum DrawCommand {
+    Rect {
+        rect: Rect,
+        color: Color,
+    },
+    RectLines {
+        rect: Rect,
+        thickness: f32,
+        color: Color,
+    },
+    /// Corner radii are ordered top-left, top-right, bottom-left, bottom-right.
+    RoundedRect {
+        rect: Rect,
+        radii: [f32; 4],
+        color: Color,
+    },
+    RoundedRectStroke {
+        rect: Rect,
+        radius: f32,
+        thickness: f32,
+        stroke: Color,
+        background: Color,
+    },
+    Line {
+        start: Vec2,
+        end: Vec2,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        color: Color,
+    },
+    Text {
+        text: String,
+        position: Vec2,
+        font: Option<Font>,
+        font_size: u16,
+        color: Color,
+    },
+    Texture {
+        texture: Texture2D,
+        position: Vec2,
+        color: Color,
+        params: DrawTextureParams,
+    },
+    /// Restricts everything after it to a screen-space rect (`None` clears the clip).
+    Clip(Option<Rect>),
+}
+
+/// Everything the UI wants to draw this frame, in order.
+///
+/// Windows and widgets only ever push commands into a `DrawList`.
+/// `UI::draw` then hands the list to `submit`, which is the only place that talks to macroquad.
+///
+/// Commands are stored in screen space. The drawing methods take coordinates relative to
+/// the current offset (see `set_offset`), which is how widgets draw relative to their window.
+#[derive(Default, Clone, Debug)]
+pub struct DrawList {
+    commands: Vec<DrawCommand>,
+    offset: Vec2,
+    fonts: FontSet,
+    clip: Option<Rect>,
+}
+
+impl DrawList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+        self.clip = None;
+    }
+
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command);
+    }
+
+    /// Moves all commands out of `other` and onto the end of this list.
+    pub fn append(&mut self, other: &mut DrawList) {
+        self.commands.append(&mut other.commands);
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        self.offset
+    }
+
+    /// Sets the origin that the drawing methods (including `set_clip`) are relative to.
+    pub fn set_offset(&mut self, offset: Vec2) {
+        self.offset = offset;
+    }
+
+    /// The fonts `draw_text_ex` falls back to for characters the given font doesn't have.
+    pub fn set_fonts(&mut self, fonts: FontSet) {
+        self.fonts = fonts;
+    }
+
+    pub fn fonts(&self) -> &FontSet {
+        &self.fonts
+    }
+
+    fn rect(&self, x: f32, y: f32, w: f32, h: f32) -> Rect {
+        Rect::new(x + self.offset.x, y + self.offset.y, w, h)
+    }
+
+    fn point(&self, x: f32, y: f32) -> Vec2 {
+        vec2(x, y) + self.offset
+    }
+
+    pub fn draw_rectangle(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        self.push(DrawCommand::Rect {
+            rect: self.rect(x, y, w, h),
+            color,
+        });
+    }
+
+    pub fn draw_rectangle_lines(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        thickness: f32,
+        color: Color,
+    ) {
+        self.push(DrawCommand::RectLines {
+            rect: self.rect(x, y, w, h),
+            thickness,
+            color,
+        });
+    }
+
+    pub fn draw_rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, radius: f32, color: Color) {
+        if radius <= 0.0 {
+            return self.draw_rectangle(x, y, w, h, color);
+        }
+
+        self.push(DrawCommand::RoundedRect {
+            rect: self.rect(x, y, w, h),
+            radii: [radius; 4],
+            color,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_rounded_rect_ex(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        r_tl: f32,
+        r_tr: f32,
+        r_bl: f32,
+        r_br: f32,
+        color: Color,
+    ) {
+        self.push(DrawCommand::RoundedRect {
+            rect: self.rect(x, y, w, h),
+            radii: [r_tl, r_tr, r_bl, r_br],
+            color,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_rounded_rect_stroke(
+        &mut self,
+        x: f32,
+        y: f32,
+        w: f32,
+        h: f32,
+        radius: f32,
+        thickness: f32,
+        stroke: Color,
+        background: Color,
+    ) {
+        self.push(DrawCommand::RoundedRectStroke {
+            rect: self.rect(x, y, w, h),
+            radius,
+            thickness,
+            stroke,
+            background,
+        });
+    }
+
+    pub fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, thickness: f32, color: Color) {
+        self.push(DrawCommand::Line {
+            start: self.point(x1, y1),
+            end: self.point(x2, y2),
+            thickness,
+            color,
+        });
+    }
+
+    pub fn draw_circle(&mut self, x: f32, y: f32, radius: f32, color: Color) {
+        self.push(DrawCommand::Circle {
+            center: self.point(x, y),
+            radius,
+            color,
+        });
+    }
+
+    /// Characters missing from `params.font` are drawn with the fallback fonts from `set_fonts`,
+    /// as separate commands.
+    pub fn draw_text_ex(&mut self, text: &str, x: f32, y: f32, params: TextParams) {
+        let mut position = self.point(x, y);
+
+        for (run, font) in self.fonts.runs(text, params.font) {
+            self.commands.push(DrawCommand::Text {
+                text: run.to_string(),
+                position,
+                font: font.cloned(),
+                font_size: params.font_size,
+                color: params.color,
+            });
+
+            position.x += self.fonts.measure_text(run, font, params.font_size, 1.0).width;
+        }
+    }
+
+    pub fn draw_texture_ex(
+        &mut self,
+        texture: &Texture2D,
+        x: f32,
+        y: f32,
+        color: Color,
+        params: DrawTextureParams,
+    ) {
+        self.push(DrawCommand::Texture {
+            texture: texture.clone(),
+            position: self.point(x, y),
+            color,
+            params,
+        });
+    }
+
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        let clip = clip.map(|r| self.rect(r.x, r.y, r.w, r.h));
+        self.restore_clip(clip);
+    }
+
+    /// The clip that's active, in screen space.
+    pub fn clip(&self) -> Option<Rect> {
+        self.clip
+    }
+
+    /// Narrows the active clip to `clip` (relative to the offset, like `set_clip`).
+    /// Returns the clip it replaced, for `restore_clip`.
+    pub fn push_clip(&mut self, clip: Rect) -> Option<Rect> {
+        let old = self.clip;
+        let clip = self.rect(clip.x, clip.y, clip.w, clip.h);
+        let clip = match old {
+            Some(old) => old.intersect(clip).unwrap_or(Rect::new(clip.x, clip.y, 0.0, 0.0)),
+            _ => clip,
+        };
+
+        self.restore_clip(Some(clip));
+        old
+    }
+
+    /// Puts back a clip returned by `push_clip` or `clip`. It's in screen space, so the offset doesn't apply.
+    pub fn restore_clip(&mut self, clip: Option<Rect>) {
+        self.clip = clip;
+        self.push(DrawCommand::Clip(clip));
+    }
+
+    /// Draws every command with macroquad, leaving no clip active.
+    pub fn submit(&self) {
+        self.submit_scaled(1.0);
+    }
+
+    /// Like `submit`, but with every position, size and font size multiplied by `scale`.
+    pub fn submit_scaled(&self, scale: f32) {
+        for command in self.commands.iter() {
+            match scale == 1.0 {
+                true => submit_command(command),
+                _ => submit_command(&command.scaled(scale)),
+            }
+        }
+
+        set_scissor(None);
+    }
+}
+
+impl DrawCommand {
+    pub fn scaled(&self, scale: f32) -> DrawCommand {
+        let rect = |r: &Rect| Rect::new(r.x * scale, r.y * scale, r.w * scale, r.h * scale);
+
+        match self {
+            DrawCommand::Rect { rect: r, color } => DrawCommand::Rect {
+                rect: rect(r),
+                color: *color,
+            },
+            DrawCommand::RectLines {
+                rect: r,
+                thickness,
+                color,
+            } => DrawCommand::RectLines {
+                rect: rect(r),
+                thickness: thickness * scale,
+                color: *color,
+            },
+            DrawCommand::RoundedRect { rect: r, radii, color } => DrawCommand::RoundedRect {
+                rect: rect(r),
+                radii: radii.map(|r| r * scale),
+                color: *color,
+            },
+            DrawCommand::RoundedRectStroke {
+                rect: r,
+                radius,
+                thickness,
+                stroke,
+                background,
+            } => DrawCommand::RoundedRectStroke {
+                rect: rect(r),
+                radius: radius * scale,
+                thickness: thickness * scale,
+                stroke: *stroke,
+                background: *background,
+            },
+            DrawCommand::Line {
+                start,
+                end,
+                thickness,
+                color,
+            } => DrawCommand::Line {
+                start: *start * scale,
+                end: *end * scale,
+                thickness: thickness * scale,
+                color: *color,
+            },
+            DrawCommand::Circle {
+                center,
+                radius,
+                color,
+            } => DrawCommand::Circle {
+                center: *center * scale,
+                radius: radius * scale,
+                color: *color,
+            },
+            // Fonts are rasterized at the scaled size, so text stays sharp
+            DrawCommand::Text {
+                text,
+                position,
+                font,
+                font_size,
+                color,
+            } => DrawCommand::Text {
+                text: text.clone(),
+                position: *position * scale,
+                font: font.clone(),
+                font_size: (*font_size as f32 * scale).round() as u16,
+                color: *color,
+            },
+            DrawCommand::Texture {
+                texture,
+                position,
+                color,
+                params,
+            } => DrawCommand::Texture {
+                texture: texture.clone(),
+                position: *position * scale,
+                color: *color,
+                params: DrawTextureParams {
+                    dest_size: Some(params.dest_size.unwrap_or_else(|| texture.size()) * scale),
+                    ..params.clone()
+                },
+            },
+            DrawCommand::Clip(clip) => DrawCommand::Clip(clip.as_ref().map(rect)),
+        }
+    }
+}
+
+fn submit_command(command: &DrawCommand) {
+    match command {
+        DrawCommand::Rect { rect, color } => draw_rectangle(rect.x, rect.y, rect.w, rect.h, *color),
+
+        DrawCommand::RectLines {
+            rect,
+            thickness,
+            color,
+        } => draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, *thickness, *color),
+
+        DrawCommand::RoundedRect { rect, radii, color } => {
+            let [r_tl, r_tr, r_bl, r_br] = *radii;
+            draw_rounded_rect_ex(rect.x, rect.y, rect.w, rect.h, r_tl, r_tr, r_bl, r_br, *color)
+        }
+
+        DrawCommand::RoundedRectStroke {
+            rect,
+            radius,
+            thickness,
+            stroke,
+            background,
+        } => draw_rounded_rect_stroke(
+            rect.x,
+            rect.y,
+            rect.w,
+            rect.h,
+            *radius,
+            *thickness,
+            *stroke,
+            *background,
+        ),
+
+        DrawCommand::Line {
+            start,
+            end,
+            thickness,
+            color,
+        } => draw_line(start.x, start.y, end.x, end.y, *thickness, *color),
+
+        DrawCommand::Circle {
+            center,
+            radius,
+            color,
+        } => draw_circle(center.x, center.y, *radius, *color),
+
+        DrawCommand::Text {
+            text,
+            position,
+            font,
+            font_size,
+            color,
+        } => {
+            draw_text_ex(
+                text,
+                position.x,
+                position.y,
+                TextParams {
+                    font: font.as_ref(),
+                    font_size: *font_size,
+                    color: *color,
+                    ..Default::default()
+                },
+            );
+        }
+
+        DrawCommand::Texture {
+            texture,
+            position,
+            color,
+            params,
+        } => draw_texture_ex(texture, position.x, position.y, *color, params.clone()),
+
+        DrawCommand::Clip(clip) => set_scissor(*clip),
+    }
+}
+
+fn set_scissor(clip: Option<Rect>) {
+    let dpi = screen_dpi_scale();
+    let clip = clip.map(|r| {
+        (
+            (r.x * dpi) as i32,
+            (r.y * dpi) as i32,
+            (r.w.max(0.0) * dpi) as i32,
+            (r.h.max(0.0) * dpi) as i32,
+        )
+    });
+
+    unsafe {
+        get_internal_gl().quad_gl.scissor(clip);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::ui::input::{GamepadButton, UiInput};
+use crate::widgets::widget_holder::WidgetIdNum;
+use crate::WindowId;
+use macroquad::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NavDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// Navigation actions for the focused widget, independent of the device they came from.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct NavInput {
+    pub next: bool,
+    pub prev: bool,
+    pub activate: bool,
+    pub cancel: bool,
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+
+    /// Move focus to the nearest widget in this direction (D-pad / stick).
+    pub move_focus: Option<NavDirection>,
+    /// Gamepad B. Closes the window, unless the focused widget used the `cancel` that comes with it.
+    pub back: bool,
+    /// Shoulder buttons, which switch the tabs of every `TabHolder` in the active window.
+    pub tab_prev: bool,
+    pub tab_next: bool,
+}
+
+impl NavInput {
+    /// Reads keyboard and gamepad buttons. The stick is handled by `FocusState`, since it needs the previous frame.
+    /// Without `keyboard`, only Escape is read from the keyboard, so text fields can still be left.
+    pub fn from_input(input: &UiInput, keyboard: bool) -> Self {
+        let shift = input.is_key_down(KeyCode::LeftShift) || input.is_key_down(KeyCode::RightShift);
+        let key = |k| keyboard && input.is_key_pressed(k);
+        let tab = key(KeyCode::Tab);
+        let pad = |b| input.is_gamepad_button_pressed(b);
+
+        let move_focus = if pad(GamepadButton::DPadUp) {
+            Some(NavDirection::Up)
+        } else if pad(GamepadButton::DPadDown) {
+            Some(NavDirection::Down)
+        } else if pad(GamepadButton::DPadLeft) {
+            Some(NavDirection::Left)
+        } else if pad(GamepadButton::DPadRight) {
+            Some(NavDirection::Right)
+        } else {
+            None
+        };
+
+        Self {
+            next: tab && !shift,
+            prev: tab && shift,
+            activate: key(KeyCode::Enter)
+                || key(KeyCode::KpEnter)
+                || key(KeyCode::Space)
+                || pad(GamepadButton::A),
+            cancel: input.is_key_pressed(KeyCode::Escape) || pad(GamepadButton::B),
+            up: key(KeyCode::Up),
+            down: key(KeyCode::Down),
+            left: key(KeyCode::Left),
+            right: key(KeyCode::Right),
+
+            move_focus,
+            back: pad(GamepadButton::B),
+            tab_prev: pad(GamepadButton::LeftShoulder),
+            tab_next: pad(GamepadButton::RightShoulder),
+        }
+    }
+
+    /// The parts of the input that go to every widget in the active window, not just the focused one.
+    pub fn broadcast(&self) -> Self {
+        Self {
+            tab_prev: self.tab_prev,
+            tab_next: self.tab_next,
+            ..Default::default()
+        }
+    }
+
+    /// What a widget focused by a click gets: `broadcast` plus `cancel`, so Space or Enter
+    /// don't press it again, but Escape still leaves a text field.
+    pub fn pointer_focused(&self) -> Self {
+        Self {
+            cancel: self.cancel,
+            ..self.broadcast()
+        }
+    }
+
+    /// Whether this moves focus from one widget to another, which is when the focus ring shows.
+    pub fn moves_focus(&self) -> bool {
+        self.next || self.prev || self.move_focus.is_some()
+    }
+
+    pub fn any(&self) -> bool {
+        *self != NavInput::default()
+    }
+}
+
+/// Which widget has keyboard focus. There's only ever one, and it lives in the active window.
+#[derive(Clone, Debug, Default)]
+pub struct FocusState {
+    pub window: Option<WindowId>,
+    pub widget: Option<WidgetIdNum>,
+    /// Whether the focus ring is drawn, and the focused widget gets keys like Space and the arrows.
+    /// It's hidden after mouse clicks and shown again once focus is moved with Tab or the gamepad.
+    pub visible: bool,
+    /// Which way the stick was pushed last frame, so holding it only moves focus once.
+    pub stick: Option<NavDirection>,
+}
+
+impl FocusState {
+    /// Moves focus along `order` (the focusable widgets of the active window, in creation order).
+    pub fn step(&mut self, order: &[(WidgetIdNum, Rect)], forward: bool) {
+        if order.is_empty() {
+            self.widget = None;
+            return;
+        }
+
+        let current = self
+            .widget
+            .and_then(|id| order.iter().position(|(x, _)| *x == id));
+
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % order.len(),
+            (Some(i), false) => (i + order.len() - 1) % order.len(),
+            (None, true) => 0,
+            (None, false) => order.len() - 1,
+        };
+
+        self.widget = Some(order[next].0);
+    }
+
+    /// Turns the stick into a one-off `NavDirection` when it gets pushed past halfway.
+    pub fn stick_direction(&mut self, stick: Vec2) -> Option<NavDirection> {
+        let direction = match stick.length() > 0.5 {
+            false => None,
+            true if stick.x.abs() > stick.y.abs() => match stick.x > 0.0 {
+                true => Some(NavDirection::Right),
+                _ => Some(NavDirection::Left),
+            },
+            true => match stick.y > 0.0 {
+                true => Some(NavDirection::Down),
+                _ => Some(NavDirection::Up),
+            },
+        };
+
+        let changed = direction != self.stick;
+        self.stick = direction;
+
+        match changed {
+            true => direction,
+            _ => None,
+        }
+    }
+
+    /// Moves focus to the closest widget whose center lies in `direction` from the focused one.
+    pub fn move_spatial(&mut self, order: &[(WidgetIdNum, Rect)], direction: NavDirection) {
+        let current = self
+            .widget
+            .and_then(|id| order.iter().find(|(x, _)| *x == id))
+            .map(|(_, r)| r.center());
+
+        let Some(from) = current else {
+            self.widget = order.first().map(|(id, _)| *id);
+            return;
+        };
+
+        let mut best: Option<(f32, WidgetIdNum)> = None;
+
+        for (id, rect) in order.iter() {
+            if Some(*id) == self.widget {
+                continue;
+            }
+
+            let d = rect.center() - from;
+
+            // Distance along the direction, and how far off to the side it is
+            let (along, across) = match direction {
+                NavDirection::Up => (-d.y, d.x),
+                NavDirection::Down => (d.y, d.x),
+                NavDirection::Left => (-d.x, d.y),
+                NavDirection::Right => (d.x, d.y),
+            };
+
+            if along <= 1.0 {
+                continue;
+            }
+
+            // Widgets off to the side count for more so focus goes straight where possible
+            let score = along + across.abs() * 2.0;
+            if best.is_none_or(|(s, _)| score < s) {
+                best = Some((score, *id));
+            }
+        }
+
+        if let Some((_, id)) = best {
+            self.widget = Some(id);
+        }
+    }
+}
+
+/// Focus bookkeeping for one window's widget update pass.
+#[derive(Clone, Debug, Default)]
+pub struct FocusContext {
+    pub focused: Option<WidgetIdNum>,
+    pub nav: NavInput,
+    /// Focusable widgets and their hit rects, in update order.
+    pub items: Vec<(WidgetIdNum, Rect)>,
+    /// A widget that asked for focus this frame (e.g. because it was clicked).
+    pub requested: Option<WidgetIdNum>,
+    /// Set when the focused widget handled `move_focus` or `cancel` itself (e.g. an open dropdown),
+    /// so the window shouldn't move focus or close.
+    pub nav_used: bool,
+    /// Set by a text widget that's being edited, so typed characters are read next frame.
+    pub text_input: bool,
+}
+
+impl FocusContext {
+    pub fn new(focused: Option<WidgetIdNum>, nav: NavInput) -> Self {
+        Self {
+            focused,
+            nav,
+            ..Default::default()
+        }
+    }
+}
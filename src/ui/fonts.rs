@@ -0,0 +1,324 @@
+use macroquad::prelude::*;
+use std::fmt::Display;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+/// JetBrains Mono, used when no font is given.
+#[cfg(feature = "embedded-font")]
+pub const EMBEDDED_FONT: &[u8] = include_bytes!("../Jetbrains.ttf");
+
+/// macroquad's built-in font, which text with no font is drawn in. Kept here to measure that text without a window.
+const DEFAULT_FONT: &[u8] = include_bytes!("../ProggyClean.ttf");
+
+fn default_glyphs() -> &'static fontdue::Font {
+    static GLYPHS: OnceLock<fontdue::Font> = OnceLock::new();
+    GLYPHS.get_or_init(|| {
+        fontdue::Font::from_bytes(DEFAULT_FONT, fontdue::FontSettings::default())
+            .expect("macroquad's default font is a valid TTF")
+    })
+}
+
+/// Measures `text` from the font's glyph metrics, the same way macroquad does at a DPI scale of 1.
+fn measure_glyphs(glyphs: &fontdue::Font, text: &str, font_size: u16, font_scale: f32) -> TextDimensions {
+    let mut width = 0.0;
+    let mut min_y = f32::MAX;
+    let mut max_y = f32::MIN;
+
+    for c in text.chars() {
+        let metrics = glyphs.metrics(c, font_size as f32);
+        let offset_y = metrics.ymin as f32 * font_scale;
+
+        width += metrics.advance_width * font_scale;
+        min_y = min_y.min(offset_y);
+        max_y = max_y.max(metrics.height as f32 * font_scale + offset_y);
+    }
+
+    TextDimensions {
+        width,
+        height: max_y - min_y,
+        offset_y: max_y,
+    }
+}
+
+/// Where to load a font from: a `.ttf`/`.otf` path, or the file's bytes (e.g. from `include_bytes!`).
+pub enum FontSource {
+    Path(PathBuf),
+    Bytes(Vec<u8>),
+}
+
+impl From<&str> for FontSource {
+    fn from(path: &str) -> Self {
+        FontSource::Path(path.into())
+    }
+}
+
+impl From<String> for FontSource {
+    fn from(path: String) -> Self {
+        FontSource::Path(path.into())
+    }
+}
+
+impl From<&Path> for FontSource {
+    fn from(path: &Path) -> Self {
+        FontSource::Path(path.into())
+    }
+}
+
+impl From<PathBuf> for FontSource {
+    fn from(path: PathBuf) -> Self {
+        FontSource::Path(path)
+    }
+}
+
+impl From<&[u8]> for FontSource {
+    fn from(bytes: &[u8]) -> Self {
+        FontSource::Bytes(bytes.to_vec())
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for FontSource {
+    fn from(bytes: &[u8; N]) -> Self {
+        FontSource::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for FontSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        FontSource::Bytes(bytes)
+    }
+}
+
+/// A font that couldn't be read or parsed, or a font name that was never added.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FontError {
+    pub message: String,
+}
+
+impl Display for FontError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// A loaded font, plus what's needed to tell which characters it has.
+#[derive(Clone)]
+pub struct UiFont {
+    pub font: Font,
+    glyphs: Arc<fontdue::Font>,
+}
+
+impl UiFont {
+    pub fn load(source: impl Into<FontSource>) -> Result<UiFont, FontError> {
+        match source.into() {
+            FontSource::Path(path) => {
+                let bytes = fs::read(&path).map_err(|e| FontError {
+                    message: format!("couldn't read font {}: {e}", path.display()),
+                })?;
+                UiFont::from_bytes(&bytes)
+            }
+            FontSource::Bytes(bytes) => UiFont::from_bytes(&bytes),
+        }
+    }
+
+    /// Like `load`, but reads the file with macroquad's `load_file`, which also works on wasm.
+    pub async fn load_async(path: &str) -> Result<UiFont, FontError> {
+        let bytes = load_file(path).await.map_err(|e| FontError {
+            message: format!("couldn't read font {path}: {e}"),
+        })?;
+        UiFont::from_bytes(&bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<UiFont, FontError> {
+        let font = load_ttf_font_from_bytes(bytes).map_err(|e| FontError {
+            message: format!("couldn't load font: {e}"),
+        })?;
+        let glyphs =
+            fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()).map_err(|e| {
+                FontError {
+                    message: format!("couldn't load font: {e}"),
+                }
+            })?;
+
+        Ok(UiFont {
+            font,
+            glyphs: Arc::new(glyphs),
+        })
+    }
+
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.glyphs.has_glyph(c)
+    }
+
+    /// Like macroquad's `measure_text`, but read straight from the font file, so it works without a window.
+    pub fn measure_text(&self, text: &str, font_size: u16, font_scale: f32) -> TextDimensions {
+        measure_glyphs(&self.glyphs, text, font_size, font_scale)
+    }
+
+    /// The bundled font with the `embedded-font` feature, otherwise `None` (macroquad's built-in font).
+    pub fn embedded() -> Option<UiFont> {
+        #[cfg(feature = "embedded-font")]
+        return Some(UiFont::from_bytes(EMBEDDED_FONT).expect("the embedded font is a valid TTF"));
+
+        #[cfg(not(feature = "embedded-font"))]
+        None
+    }
+}
+
+impl std::fmt::Debug for UiFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UiFont").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FontStyle {
+    Regular,
+    Bold,
+    Italic,
+    Monospace,
+}
+
+/// The fonts a theme draws with: one per `FontStyle`, and a fallback chain for characters they don't have.
+///
+/// Glyph coverage is always checked against the regular font (or macroquad's built-in font, which is
+/// only ASCII), so a bold or italic font should cover the same characters as the regular one.
+#[derive(Clone, Default, Debug)]
+pub struct FontSet {
+    regular: Option<UiFont>,
+    bold: Option<UiFont>,
+    italic: Option<UiFont>,
+    monospace: Option<UiFont>,
+    fallbacks: Vec<UiFont>,
+}
+
+impl FontSet {
+    pub fn get(&self, style: FontStyle) -> Option<&UiFont> {
+        match style {
+            FontStyle::Regular => self.regular.as_ref(),
+            FontStyle::Bold => self.bold.as_ref(),
+            FontStyle::Italic => self.italic.as_ref(),
+            FontStyle::Monospace => self.monospace.as_ref(),
+        }
+    }
+
+    pub fn set(&mut self, style: FontStyle, font: Option<UiFont>) -> &mut Self {
+        match style {
+            FontStyle::Regular => self.regular = font,
+            FontStyle::Bold => self.bold = font,
+            FontStyle::Italic => self.italic = font,
+            FontStyle::Monospace => self.monospace = font,
+        }
+        self
+    }
+
+    pub fn fallbacks(&self) -> &[UiFont] {
+        &self.fallbacks
+    }
+
+    /// Characters missing from the regular font are drawn with the first fallback that has them.
+    pub fn add_fallback(&mut self, font: UiFont) -> &mut Self {
+        self.fallbacks.push(font);
+        self
+    }
+
+    pub fn clear_fallbacks(&mut self) -> &mut Self {
+        self.fallbacks.clear();
+        self
+    }
+
+    /// Which fallback to draw `c` with, or `None` for the font it was asked to be drawn with.
+    fn fallback_for(&self, c: char) -> Option<usize> {
+        let covered = match &self.regular {
+            Some(font) => font.has_glyph(c),
+            None => c.is_ascii(),
+        };
+
+        match covered {
+            true => None,
+            _ => self.fallbacks.iter().position(|f| f.has_glyph(c)),
+        }
+    }
+
+    /// Splits `text` into pieces that each come from a single font, in order.
+    pub fn runs<'a>(
+        &'a self,
+        text: &'a str,
+        font: Option<&'a Font>,
+    ) -> Vec<(&'a str, Option<&'a Font>)> {
+        if self.fallbacks.is_empty() {
+            return vec![(text, font)];
+        }
+
+        let pick = |fallback: Option<usize>| match fallback {
+            Some(i) => Some(&self.fallbacks[i].font),
+            None => font,
+        };
+
+        let mut runs = vec![];
+        let mut start = 0;
+        let mut current = None;
+
+        for (i, c) in text.char_indices() {
+            let fallback = self.fallback_for(c);
+            if fallback != current && i > start {
+                runs.push((&text[start..i], pick(current)));
+                start = i;
+            }
+            current = fallback;
+        }
+
+        if start < text.len() {
+            runs.push((&text[start..], pick(current)));
+        }
+
+        runs
+    }
+
+    /// `measure_text`, but with characters from fallback fonts measured in those fonts.
+    ///
+    /// Text in macroquad's built-in font (`None`), or in a font borrowed from this set, is measured from
+    /// the font file, so laying it out doesn't need a window. Other fonts go through macroquad.
+    pub fn measure_text(
+        &self,
+        text: &str,
+        font: Option<&Font>,
+        font_size: u16,
+        font_scale: f32,
+    ) -> TextDimensions {
+        if self.fallbacks.is_empty() {
+            return self.measure_run(text, font, font_size, font_scale);
+        }
+
+        self.runs(text, font)
+            .into_iter()
+            .fold(TextDimensions::default(), |total, (run, font)| {
+                let dim = self.measure_run(run, font, font_size, font_scale);
+                TextDimensions {
+                    width: total.width + dim.width,
+                    height: total.height.max(dim.height),
+                    offset_y: total.offset_y.max(dim.offset_y),
+                }
+            })
+    }
+
+    fn measure_run(&self, text: &str, font: Option<&Font>, font_size: u16, font_scale: f32) -> TextDimensions {
+        let Some(font) = font else {
+            return measure_glyphs(default_glyphs(), text, font_size, font_scale);
+        };
+
+        let slots = [&self.regular, &self.bold, &self.italic, &self.monospace];
+        let own = slots
+            .into_iter()
+            .flatten()
+            .chain(&self.fallbacks)
+            .find(|f| std::ptr::eq(&f.font, font));
+
+        match own {
+            Some(f) => f.measure_text(text, font_size, font_scale),
+            None => measure_text(text, Some(font), font_size, font_scale),
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use macroquad::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+/// Controller buttons the UI understands, named after the Xbox layout.
+///
+/// macroquad doesn't read gamepads, so `MacroquadInput` never reports these.
+/// Feed them in from your own `InputSource` (e.g. one backed by gilrs).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    A,
+    B,
+    X,
+    Y,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    LeftShoulder,
+    RightShoulder,
+    Start,
+    Select,
+}
+
+/// Everything the UI reads from the outside world during a single frame.
+///
+/// Windows and widgets never query macroquad for input directly, they only
+/// look at the snapshot passed into `UI::draw`. This makes it possible to
+/// drive the UI from scripted input (see `ScriptedInput`).
+#[derive(Clone, Debug, Default)]
+pub struct UiInput {
+    pub mouse: Vec2,
+    pub mouse_down: HashSet<MouseButton>,
+    pub mouse_pressed: HashSet<MouseButton>,
+    pub mouse_released: HashSet<MouseButton>,
+    pub wheel: Vec2,
+
+    pub keys_down: HashSet<KeyCode>,
+    pub keys_pressed: HashSet<KeyCode>,
+    pub keys_released: HashSet<KeyCode>,
+    pub last_key_pressed: Option<KeyCode>,
+    pub chars: Vec<char>,
+
+    pub gamepad_down: HashSet<GamepadButton>,
+    pub gamepad_pressed: HashSet<GamepadButton>,
+    pub gamepad_released: HashSet<GamepadButton>,
+    /// Left stick position, each axis in -1..1 with +y pointing down.
+    pub left_stick: Vec2,
+
+    pub frame_time: f32,
+    pub time: f64,
+    pub screen_size: Vec2,
+}
+
+impl UiInput {
+    pub fn new(screen_size: Vec2) -> Self {
+        Self {
+            screen_size,
+            frame_time: 1.0 / 60.0,
+            ..Default::default()
+        }
+    }
+
+    /// Reads the current frame's input from macroquad.
+    /// Typed characters are only taken out of macroquad's queue if `read_chars` is set.
+    pub fn from_macroquad(read_chars: bool) -> Self {
+        let buttons = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+        let wheel = mouse_wheel();
+
+        let mut chars = vec![];
+        if read_chars {
+            while let Some(c) = get_char_pressed() {
+                chars.push(c);
+            }
+        }
+
+        Self {
+            mouse: mouse_position().into(),
+            mouse_down: buttons
+                .into_iter()
+                .filter(|b| is_mouse_button_down(*b))
+                .collect(),
+            mouse_pressed: buttons
+                .into_iter()
+                .filter(|b| is_mouse_button_pressed(*b))
+                .collect(),
+            mouse_released: buttons
+                .into_iter()
+                .filter(|b| is_mouse_button_released(*b))
+                .collect(),
+            wheel: vec2(wheel.0, wheel.1),
+
+            keys_down: get_keys_down(),
+            keys_pressed: get_keys_pressed(),
+            keys_released: get_keys_released(),
+            last_key_pressed: get_last_key_pressed(),
+            chars,
+
+            gamepad_down: HashSet::new(),
+            gamepad_pressed: HashSet::new(),
+            gamepad_released: HashSet::new(),
+            left_stick: Vec2::ZERO,
+
+            frame_time: get_frame_time(),
+            time: get_time(),
+            screen_size: vec2(screen_width(), screen_height()),
+        }
+    }
+
+    /// The same input in a space that's `scale` times bigger, e.g. for a UI drawn at 2x.
+    pub fn scaled(&self, scale: f32) -> UiInput {
+        UiInput {
+            mouse: self.mouse / scale,
+            screen_size: self.screen_size / scale,
+            ..self.clone()
+        }
+    }
+
+    pub fn mouse_position(&self) -> Vec2 {
+        self.mouse
+    }
+
+    pub fn mouse_wheel(&self) -> (f32, f32) {
+        (self.wheel.x, self.wheel.y)
+    }
+
+    pub fn is_mouse_button_down(&self, button: MouseButton) -> bool {
+        self.mouse_down.contains(&button)
+    }
+
+    pub fn is_mouse_button_pressed(&self, button: MouseButton) -> bool {
+        self.mouse_pressed.contains(&button)
+    }
+
+    pub fn is_mouse_button_released(&self, button: MouseButton) -> bool {
+        self.mouse_released.contains(&button)
+    }
+
+    pub fn is_key_down(&self, key: KeyCode) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    pub fn is_key_pressed(&self, key: KeyCode) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    pub fn is_key_released(&self, key: KeyCode) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_gamepad_button_down(&self, button: GamepadButton) -> bool {
+        self.gamepad_down.contains(&button)
+    }
+
+    pub fn is_gamepad_button_pressed(&self, button: GamepadButton) -> bool {
+        self.gamepad_pressed.contains(&button)
+    }
+
+    pub fn is_gamepad_button_released(&self, button: GamepadButton) -> bool {
+        self.gamepad_released.contains(&button)
+    }
+
+    pub fn screen_width(&self) -> f32 {
+        self.screen_size.x
+    }
+
+    pub fn screen_height(&self) -> f32 {
+        self.screen_size.y
+    }
+
+    /// The state carried over into the next frame: held buttons and keys stay
+    /// down (and the stick stays where it is), everything that only lasts for one frame is cleared.
+    pub fn next_frame(&self) -> Self {
+        Self {
+            mouse: self.mouse,
+            mouse_down: self.mouse_down.clone(),
+            keys_down: self.keys_down.clone(),
+            gamepad_down: self.gamepad_down.clone(),
+            left_stick: self.left_stick,
+            frame_time: self.frame_time,
+            time: self.time + self.frame_time as f64,
+            screen_size: self.screen_size,
+            ..Default::default()
+        }
+    }
+}
+
+/// Something that can produce a `UiInput` snapshot once per frame.
+pub trait InputSource {
+    fn poll(&mut self) -> UiInput;
+
+    /// Called before each `poll` with whether a text widget is being edited.
+    /// Sources that share a character queue with the app can leave it alone when it isn't.
+    fn set_text_input(&mut self, _active: bool) {}
+}
+
+/// The default input source, reading straight from macroquad.
+///
+/// Typed characters are only read while a text widget is being edited,
+/// so the app still gets them from `get_char_pressed` the rest of the time.
+#[derive(Default)]
+pub struct MacroquadInput {
+    text_input: bool,
+}
+
+impl InputSource for MacroquadInput {
+    fn poll(&mut self) -> UiInput {
+        UiInput::from_macroquad(self.text_input)
+    }
+
+    fn set_text_input(&mut self, active: bool) {
+        self.text_input = active;
+    }
+}
+
+/// A queue of pre-recorded frames, useful for driving the UI in tests.
+///
+/// Each call to `poll` consumes one frame. Once the queue runs dry the last
+/// frame is repeated with its one-frame events (presses, releases, chars,
+/// wheel) cleared, so held buttons and keys stay held.
+pub struct ScriptedInput {
+    frames: VecDeque<UiInput>,
+    last: UiInput,
+}
+
+impl ScriptedInput {
+    pub fn new(screen_size: Vec2) -> Self {
+        Self {
+            frames: VecDeque::new(),
+            last: UiInput::new(screen_size),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Queues a raw frame as-is.
+    pub fn push(&mut self, frame: UiInput) -> &mut Self {
+        self.frames.push_back(frame);
+        self
+    }
+
+    /// Queues a frame derived from the last queued one.
+    pub fn frame(&mut self, f: impl FnOnce(&mut UiInput)) -> &mut Self {
+        let mut frame = self.tail().next_frame();
+        f(&mut frame);
+        self.push(frame)
+    }
+
+    pub fn idle(&mut self, frames: usize) -> &mut Self {
+        for _ in 0..frames {
+            self.frame(|_| {});
+        }
+        self
+    }
+
+    pub fn move_mouse(&mut self, position: Vec2) -> &mut Self {
+        self.frame(|f| f.mouse = position)
+    }
+
+    pub fn mouse_down(&mut self, button: MouseButton) -> &mut Self {
+        self.frame(|f| {
+            f.mouse_pressed.insert(button);
+            f.mouse_down.insert(button);
+        })
+    }
+
+    pub fn mouse_up(&mut self, button: MouseButton) -> &mut Self {
+        self.frame(|f| {
+            f.mouse_released.insert(button);
+            f.mouse_down.remove(&button);
+        })
+    }
+
+    /// Moves the mouse to `position`, then presses and releases the left button.
+    pub fn click(&mut self, position: Vec2) -> &mut Self {
+        self.move_mouse(position)
+            .mouse_down(MouseButton::Left)
+            .mouse_up(MouseButton::Left)
+    }
+
+    /// Presses the left button at `from`, moves to `to` and releases it there.
+    pub fn drag(&mut self, from: Vec2, to: Vec2) -> &mut Self {
+        self.move_mouse(from)
+            .mouse_down(MouseButton::Left)
+            .move_mouse(to)
+            .mouse_up(MouseButton::Left)
+    }
+
+    pub fn scroll(&mut self, wheel: Vec2) -> &mut Self {
+        self.frame(|f| f.wheel = wheel)
+    }
+
+    pub fn key_down(&mut self, key: KeyCode) -> &mut Self {
+        self.frame(|f| {
+            f.keys_pressed.insert(key);
+            f.keys_down.insert(key);
+            f.last_key_pressed = Some(key);
+        })
+    }
+
+    pub fn key_up(&mut self, key: KeyCode) -> &mut Self {
+        self.frame(|f| {
+            f.keys_released.insert(key);
+            f.keys_down.remove(&key);
+        })
+    }
+
+    /// Presses and releases `key` over two frames.
+    pub fn key(&mut self, key: KeyCode) -> &mut Self {
+        self.key_down(key).key_up(key)
+    }
+
+    pub fn gamepad_down(&mut self, button: GamepadButton) -> &mut Self {
+        self.frame(|f| {
+            f.gamepad_pressed.insert(button);
+            f.gamepad_down.insert(button);
+        })
+    }
+
+    pub fn gamepad_up(&mut self, button: GamepadButton) -> &mut Self {
+        self.frame(|f| {
+            f.gamepad_released.insert(button);
+            f.gamepad_down.remove(&button);
+        })
+    }
+
+    /// Presses and releases a gamepad button over two frames.
+    pub fn gamepad_button(&mut self, button: GamepadButton) -> &mut Self {
+        self.gamepad_down(button).gamepad_up(button)
+    }
+
+    /// Moves the left stick to `position` and leaves it there.
+    pub fn stick(&mut self, position: Vec2) -> &mut Self {
+        self.frame(|f| f.left_stick = position)
+    }
+
+    /// Types `text`, one character per frame.
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.frame(|f| f.chars.push(c));
+        }
+        self
+    }
+
+    fn tail(&self) -> &UiInput {
+        self.frames.back().unwrap_or(&self.last)
+    }
+}
+
+impl InputSource for ScriptedInput {
+    fn poll(&mut self) -> UiInput {
+        let frame = match self.frames.pop_front() {
+            Some(frame) => frame,
+            None => self.last.next_frame(),
+        };
+        self.last = frame.clone();
+        frame
+    }
+}
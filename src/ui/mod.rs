@@ -1,4 +1,9 @@
+pub mod draw_list;
+pub mod focus;
+pub mod fonts;
+pub mod input;
 pub mod mouse_action;
 pub mod windows;
 
+#[allow(clippy::module_inception)]
 pub mod ui;
@@ -1,18 +1,32 @@
 use crate::ui::windows::window_handler::WindowId;
+use macroquad::miniquad::CursorIcon;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum MouseAction {
     WindowHover(WindowId),
+    /// A splitter, tab or dock space is being dragged, so windows ignore the mouse.
+    Dock,
     Normal,
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct WidgetAction {
     pub taken: bool,
+    /// The mouse cursor a widget asked for this frame.
+    pub cursor: Option<CursorIcon>,
+}
+
+impl Default for WidgetAction {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl WidgetAction {
     pub fn new() -> Self {
-        Self { taken: false }
+        Self {
+            taken: false,
+            cursor: None,
+        }
     }
 }
@@ -1,28 +1,256 @@
+use crate::ui::draw_list::DrawList;
+use crate::ui::fonts::{FontError, FontSource, FontStyle, UiFont};
+use crate::ui::input::{InputSource, MacroquadInput, UiInput};
 use crate::ui::windows::window::Window;
+use crate::{Error, IdConflictPolicy};
 use crate::ui::windows::window_handler::WindowHandler;
+use crate::ui::windows::window_theme::{ThemeError, ThemePreset, ThemeWatcher, WindowTheme};
+use macroquad::miniquad::window::set_mouse_cursor;
+use macroquad::miniquad::CursorIcon;
+use macroquad::prelude::screen_dpi_scale;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 pub struct UI {
     pub taken: bool,
     handler: WindowHandler,
+    input: Box<dyn InputSource>,
+    draw_list: DrawList,
+    theme_watcher: Option<ThemeWatcher>,
+    theme_error: Option<ThemeError>,
+    scale: f32,
+    dpi_scaling: bool,
+    fonts: HashMap<String, UiFont>,
 }
 
 impl UI {
+    /// Uses the font at `font_path`, falling back to the default font if it can't be loaded.
     pub async fn new(font_path: Option<&str>) -> Self {
+        Self::with_theme(WindowTheme::new(font_path).await)
+    }
+
+    /// Like `new`, but returns an error if the font at `font_path` can't be loaded.
+    pub async fn try_new(font_path: Option<&str>) -> Result<Self, Error> {
+        Ok(Self::with_theme(WindowTheme::try_new(font_path).await?))
+    }
+
+    /// Uses a font from memory, e.g. `include_bytes!("assets/Inter.ttf")`.
+    pub fn new_with_font_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::with_theme(WindowTheme::from_font_bytes(bytes)?))
+    }
+
+    pub fn with_theme(theme: WindowTheme) -> Self {
         Self {
-            handler: WindowHandler::new(font_path).await,
+            handler: WindowHandler::with_theme(theme),
             taken: false,
+            input: Box::new(MacroquadInput::default()),
+            draw_list: DrawList::new(),
+            theme_watcher: None,
+            theme_error: None,
+            scale: 1.0,
+            dpi_scaling: false,
+            fonts: HashMap::new(),
         }
     }
 
+    /// Replaces where `draw` reads its input from (macroquad by default).
+    pub fn set_input_source(&mut self, source: impl InputSource + 'static) -> &mut Self {
+        self.input = Box::new(source);
+        self
+    }
+
+    pub fn theme(&self) -> &WindowTheme {
+        self.handler.theme()
+    }
+
+    /// Changes the theme of every window, including ones that are already open.
+    pub fn set_theme(&mut self, theme: WindowTheme) -> &mut Self {
+        self.handler.set_theme(theme);
+        self
+    }
+
+    /// Switches to the colors of a built-in preset, keeping the current font and metrics.
+    pub fn set_theme_preset(&mut self, preset: ThemePreset) -> &mut Self {
+        let mut theme = self.theme().clone();
+        theme.set_colors(&preset.theme());
+        self.set_theme(theme)
+    }
+
+    /// Loads a theme file on top of the current theme, and again every time the file changes.
+    /// Only the keys in the file are changed, so `set_font`, `set_theme_preset` and the like still work
+    /// alongside it, and keys taken out of the file go back to what they were.
+    /// If the file can't be loaded the theme is left alone and the error shows up in `theme_error`.
+    pub fn watch_theme(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.theme_watcher = Some(ThemeWatcher::new(path.into(), self.theme().clone()));
+        self
+    }
+
+    pub fn stop_watching_theme(&mut self) -> &mut Self {
+        self.theme_watcher = None;
+        self
+    }
+
+    /// Why the watched theme file last failed to load, if it did.
+    pub fn theme_error(&self) -> Option<&ThemeError> {
+        self.theme_error.as_ref()
+    }
+
+    /// Loads a font from a path or bytes and registers it under `name`, replacing any font with that name.
+    pub fn add_font(
+        &mut self,
+        name: impl ToString,
+        source: impl Into<FontSource>,
+    ) -> Result<&mut Self, FontError> {
+        self.fonts.insert(name.to_string(), UiFont::load(source)?);
+        Ok(self)
+    }
+
+    /// A font added with `add_font`, e.g. for `Window::text_ex`.
+    pub fn font(&self, name: &str) -> Option<&UiFont> {
+        self.fonts.get(name)
+    }
+
+    /// Makes every window use the font called `name` for `style`.
+    /// Titles are bold, and everything else is regular unless a widget asks for another style.
+    pub fn set_font(&mut self, style: FontStyle, name: &str) -> Result<&mut Self, FontError> {
+        let font = self.named_font(name)?;
+        let mut theme = self.theme().clone();
+        theme.set_font(style, Some(font));
+        Ok(self.set_theme(theme))
+    }
+
+    /// Adds the font called `name` to the end of the fallback chain,
+    /// which is used for characters the regular font doesn't have (CJK, symbols, emoji).
+    pub fn add_fallback_font(&mut self, name: &str) -> Result<&mut Self, FontError> {
+        let font = self.named_font(name)?;
+        let mut theme = self.theme().clone();
+        theme.fonts.add_fallback(font);
+        Ok(self.set_theme(theme))
+    }
+
+    fn named_font(&self, name: &str) -> Result<UiFont, FontError> {
+        self.fonts.get(name).cloned().ok_or_else(|| FontError {
+            message: format!("no font named `{name}`, add it with `add_font` first"),
+        })
+    }
+
+    /// How much bigger than its pixel sizes the UI is drawn, including `screen_dpi_scale` if `use_dpi_scale` is on.
+    pub fn scale(&self) -> f32 {
+        match self.dpi_scaling {
+            true => self.scale * screen_dpi_scale(),
+            _ => self.scale,
+        }
+    }
+
+    /// Draws the whole UI `scale` times bigger: fonts, paddings, the titlebar and hit areas.
+    /// Window positions and sizes are in these scaled units too.
+    pub fn set_scale(&mut self, scale: f32) -> &mut Self {
+        self.scale = scale.max(0.1);
+        self
+    }
+
+    /// Multiplies the scale by the display's DPI scale, so the UI is the same physical size on every screen.
+    pub fn use_dpi_scale(&mut self, enabled: bool) -> &mut Self {
+        self.dpi_scaling = enabled;
+        self
+    }
+
+    /// What to do when two widgets in a window share an ID. Panics by default.
+    /// `IdConflictPolicy::Highlight` is handy while developing, to see which widgets need explicit IDs.
+    pub fn set_id_conflict_policy(&mut self, policy: IdConflictPolicy) -> &mut Self {
+        self.handler.set_id_conflict_policy(policy);
+        self
+    }
+
+    /// Turns Tab focus, and Space/Enter/arrow keys on the focused widget, on or off. On by default.
+    /// Gamepad navigation isn't affected.
+    pub fn set_keyboard_nav(&mut self, enabled: bool) -> &mut Self {
+        self.handler.set_keyboard_nav(enabled);
+        self
+    }
+
+    /// Whether a textbox or text editor is being edited, and so takes the characters typed.
+    /// Otherwise `draw` leaves macroquad's character queue alone for the rest of the app.
+    pub fn wants_text_input(&self) -> bool {
+        self.handler.wants_text_input()
+    }
+
+    /// The mouse cursor for the last frame, e.g. a text cursor over a textbox or a resize arrow on a window edge.
+    /// `draw` sets it, `record` leaves that to the caller.
+    pub fn cursor(&self) -> CursorIcon {
+        self.handler.cursor()
+    }
+
     pub fn begin(&mut self, id: impl ToString) -> &mut Window {
         self.handler.begin(id)
     }
 
     pub fn draw(&mut self) -> bool {
-        let taken = self.handler.start_windows();
-        self.taken = taken;
-        self.handler.end_windows();
+        self.input.set_text_input(self.wants_text_input());
+        let input = self.input.poll();
+        self.draw_with_input(&input)
+    }
+
+    /// Same as `draw`, but uses the given input snapshot instead of polling the input source.
+    pub fn draw_with_input(&mut self, input: &UiInput) -> bool {
+        self.record(input);
+        self.draw_list.submit_scaled(self.scale());
+        set_mouse_cursor(self.cursor());
+
+        self.taken
+    }
+
+    /// Updates the windows and records this frame's draw commands without drawing them.
+    /// The commands are unscaled, see `DrawList::submit_scaled`.
+    pub fn record(&mut self, input: &UiInput) -> &DrawList {
+        let scale = self.scale();
+        let scaled;
+        let input = match scale == 1.0 {
+            true => input,
+            _ => {
+                scaled = input.scaled(scale);
+                &scaled
+            }
+        };
+
+        self.reload_theme(input.time);
+
+        self.taken = self.handler.start_windows(input);
+
+        self.draw_list.clear();
+        self.handler.end_windows(&mut self.draw_list);
+
+        &self.draw_list
+    }
+
+    /// Serializes every window's position, size, open and collapsed state, scroll and stack order
+    /// into an ini-like string, keyed by the id passed to `begin`.
+    pub fn save_layout(&self) -> String {
+        self.handler.save_layout()
+    }
+
+    /// Restores a layout from `save_layout`. Windows that haven't been created yet get theirs when they are.
+    pub fn load_layout(&mut self, text: &str) {
+        self.handler.load_layout(text);
+    }
+
+    fn reload_theme(&mut self, time: f64) {
+        let theme = self.handler.theme();
+        let Some(result) = self.theme_watcher.as_mut().and_then(|w| w.poll(time, theme)) else {
+            return;
+        };
+
+        match result {
+            Ok(theme) => {
+                self.handler.set_theme(theme);
+                self.theme_error = None;
+            }
+            Err(e) => self.theme_error = Some(e),
+        }
+    }
 
-        taken
+    /// The draw commands recorded for the last frame.
+    pub fn draw_list(&self) -> &DrawList {
+        &self.draw_list
     }
 }
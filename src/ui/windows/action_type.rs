@@ -1,15 +1,10 @@
-#[derive(PartialEq, Clone, Copy)]
+#[derive(PartialEq, Clone, Copy, Default)]
 pub enum ActionType {
     Once,
+    #[default]
     EachFrame,
 }
 
-impl Default for ActionType {
-    fn default() -> Self {
-        ActionType::EachFrame
-    }
-}
-
 impl From<()> for ActionType {
     fn from(_: ()) -> Self {
         ActionType::EachFrame
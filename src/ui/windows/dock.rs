@@ -0,0 +1,560 @@
+use crate::ui::windows::window_handler::WindowId;
+use crate::ui::windows::window_layout::{DockLayout, DockNodeLayout};
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+/// The smallest a floating dock space can be resized to.
+pub const MIN_FLOATING_DOCK_SIZE: Vec2 = vec2(150.0, 100.0);
+/// How far a tab has to be dragged before it comes out of its dock.
+pub const TAB_DRAG_DISTANCE: f32 = 6.0;
+/// The bottom-right corner of a floating dock space that resizes it.
+pub const DOCK_RESIZE_CORNER: f32 = 12.0;
+
+/// Where a window gets dropped relative to the window (or screen) it's dropped on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DockSide {
+    Center,
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitAxis {
+    /// Children side by side.
+    Horizontal,
+    /// Children stacked on top of each other.
+    Vertical,
+}
+
+/// Takes you from the root of a dock tree to a node: `false` is the first child of a split, `true` the second.
+pub type DockPath = Vec<bool>;
+
+pub enum DockNode {
+    /// A set of windows sharing a tab bar. The screen's central node is an empty leaf that never gets removed.
+    Leaf {
+        windows: Vec<WindowId>,
+        active: usize,
+        central: bool,
+    },
+    Split {
+        axis: SplitAxis,
+        /// How much of the space the first child gets.
+        ratio: f32,
+        first: Box<DockNode>,
+        second: Box<DockNode>,
+    },
+}
+
+pub struct DockLeaf {
+    pub path: DockPath,
+    pub rect: Rect,
+    pub windows: Vec<WindowId>,
+    pub active: usize,
+}
+
+pub struct DockSplit {
+    pub path: DockPath,
+    pub rect: Rect,
+    pub axis: SplitAxis,
+    pub ratio: f32,
+}
+
+impl DockSplit {
+    /// The draggable band between the two children.
+    pub fn splitter_rect(&self) -> Rect {
+        match self.axis {
+            SplitAxis::Horizontal => Rect::new(
+                self.rect.x + self.rect.w * self.ratio - 3.0,
+                self.rect.y,
+                6.0,
+                self.rect.h,
+            ),
+            SplitAxis::Vertical => Rect::new(
+                self.rect.x,
+                self.rect.y + self.rect.h * self.ratio - 3.0,
+                self.rect.w,
+                6.0,
+            ),
+        }
+    }
+}
+
+impl DockNode {
+    pub fn leaf(windows: Vec<WindowId>) -> Self {
+        DockNode::Leaf {
+            windows,
+            active: 0,
+            central: false,
+        }
+    }
+
+    pub fn central() -> Self {
+        DockNode::Leaf {
+            windows: vec![],
+            active: 0,
+            central: true,
+        }
+    }
+
+    /// The path to the leaf holding `id`.
+    pub fn find(&self, id: WindowId) -> Option<DockPath> {
+        match self {
+            DockNode::Leaf { windows, .. } => windows.contains(&id).then(Vec::new),
+            DockNode::Split { first, second, .. } => {
+                for (side, child) in [(false, first), (true, second)] {
+                    if let Some(mut path) = child.find(id) {
+                        path.insert(0, side);
+                        return Some(path);
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    pub fn node_mut(&mut self, path: &[bool]) -> Option<&mut DockNode> {
+        match (path.split_first(), self) {
+            (None, node) => Some(node),
+            (Some((side, rest)), DockNode::Split { first, second, .. }) => match side {
+                false => first.node_mut(rest),
+                true => second.node_mut(rest),
+            },
+            _ => None,
+        }
+    }
+
+    /// Adds `id` as a new tab of this leaf and makes it the visible one.
+    pub fn add_tab(&mut self, id: WindowId) {
+        if let DockNode::Leaf {
+            windows, active, ..
+        } = self
+        {
+            windows.push(id);
+            *active = windows.len() - 1;
+        }
+    }
+
+    /// Replaces this node with a split of itself and a new leaf holding `id` on the given side.
+    /// `ratio` is how much of the space the new leaf gets.
+    pub fn split(&mut self, side: DockSide, id: WindowId, ratio: f32) {
+        let (axis, new_first) = match side {
+            DockSide::Left => (SplitAxis::Horizontal, true),
+            DockSide::Right => (SplitAxis::Horizontal, false),
+            DockSide::Top => (SplitAxis::Vertical, true),
+            DockSide::Bottom => (SplitAxis::Vertical, false),
+            DockSide::Center => return self.add_tab(id),
+        };
+
+        let old = Box::new(std::mem::replace(self, DockNode::leaf(vec![])));
+        let new = Box::new(DockNode::leaf(vec![id]));
+
+        *self = match new_first {
+            true => DockNode::Split {
+                axis,
+                ratio,
+                first: new,
+                second: old,
+            },
+            _ => DockNode::Split {
+                axis,
+                ratio: 1.0 - ratio,
+                first: old,
+                second: new,
+            },
+        };
+    }
+
+    /// Removes `id` from whichever leaf has it, then collapses any leaves that ended up empty.
+    pub fn remove(&mut self, id: WindowId) {
+        self.retain(&|x| x != id);
+    }
+
+    /// Keeps only the windows `f` returns true for, collapsing empty leaves into their sibling.
+    pub fn retain(&mut self, f: &dyn Fn(WindowId) -> bool) {
+        match self {
+            DockNode::Leaf {
+                windows, active, ..
+            } => {
+                let visible = windows.get(*active).copied();
+                windows.retain(|x| f(*x));
+
+                *active = visible
+                    .and_then(|v| windows.iter().position(|x| *x == v))
+                    .unwrap_or(0)
+                    .min(windows.len().saturating_sub(1));
+            }
+            DockNode::Split { first, second, .. } => {
+                first.retain(f);
+                second.retain(f);
+
+                if first.is_removable() {
+                    *self = std::mem::replace(second.as_mut(), DockNode::leaf(vec![]));
+                } else if second.is_removable() {
+                    *self = std::mem::replace(first.as_mut(), DockNode::leaf(vec![]));
+                }
+            }
+        }
+    }
+
+    fn is_removable(&self) -> bool {
+        matches!(self, DockNode::Leaf { windows, central: false, .. } if windows.is_empty())
+    }
+
+    pub fn windows(&self) -> Vec<WindowId> {
+        match self {
+            DockNode::Leaf { windows, .. } => windows.clone(),
+            DockNode::Split { first, second, .. } => {
+                let mut windows = first.windows();
+                windows.extend(second.windows());
+                windows
+            }
+        }
+    }
+
+    /// The tree with windows by name, for saving. Windows `name` doesn't know are left out.
+    pub fn to_layout(&self, name: &dyn Fn(WindowId) -> Option<String>) -> DockNodeLayout {
+        match self {
+            DockNode::Leaf {
+                windows,
+                active,
+                central,
+            } => DockNodeLayout::Leaf {
+                windows: windows.iter().filter_map(|id| name(*id)).collect(),
+                active: *active,
+                central: *central,
+            },
+            DockNode::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => DockNodeLayout::Split {
+                axis: *axis,
+                ratio: *ratio,
+                first: Box::new(first.to_layout(name)),
+                second: Box::new(second.to_layout(name)),
+            },
+        }
+    }
+
+    /// Rebuilds a saved tree, turning window names back into ids.
+    pub fn from_layout(layout: &DockNodeLayout, id: &dyn Fn(&str) -> WindowId) -> Self {
+        match layout {
+            DockNodeLayout::Leaf {
+                windows,
+                active,
+                central,
+            } => DockNode::Leaf {
+                windows: windows.iter().map(|name| id(name)).collect(),
+                active: *active,
+                central: *central,
+            },
+            DockNodeLayout::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => DockNode::Split {
+                axis: *axis,
+                ratio: ratio.clamp(0.1, 0.9),
+                first: Box::new(DockNode::from_layout(first, id)),
+                second: Box::new(DockNode::from_layout(second, id)),
+            },
+        }
+    }
+
+    /// Works out the rect of every leaf and split, given the rect of this node.
+    pub fn layout(
+        &self,
+        rect: Rect,
+        path: &mut DockPath,
+        leaves: &mut Vec<DockLeaf>,
+        splits: &mut Vec<DockSplit>,
+    ) {
+        match self {
+            DockNode::Leaf {
+                windows, active, ..
+            } => {
+                if !windows.is_empty() {
+                    leaves.push(DockLeaf {
+                        path: path.clone(),
+                        rect,
+                        windows: windows.clone(),
+                        active: *active,
+                    });
+                }
+            }
+            DockNode::Split {
+                axis,
+                ratio,
+                first,
+                second,
+            } => {
+                let (a, b) = match axis {
+                    SplitAxis::Horizontal => {
+                        let w = (rect.w * ratio).floor();
+                        (
+                            Rect::new(rect.x, rect.y, w, rect.h),
+                            Rect::new(rect.x + w, rect.y, rect.w - w, rect.h),
+                        )
+                    }
+                    SplitAxis::Vertical => {
+                        let h = (rect.h * ratio).floor();
+                        (
+                            Rect::new(rect.x, rect.y, rect.w, h),
+                            Rect::new(rect.x, rect.y + h, rect.w, rect.h - h),
+                        )
+                    }
+                };
+
+                splits.push(DockSplit {
+                    path: path.clone(),
+                    rect,
+                    axis: *axis,
+                    ratio: *ratio,
+                });
+
+                path.push(false);
+                first.layout(a, path, leaves, splits);
+                path.pop();
+
+                path.push(true);
+                second.layout(b, path, leaves, splits);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// A tree of docked windows filling `rect`.
+pub type DockSpaceId = u64;
+
+pub struct DockSpace {
+    /// Stays the same while other dock spaces come and go, unlike its index in `Docking::spaces`.
+    pub id: DockSpaceId,
+    pub rect: Rect,
+    pub root: DockNode,
+    /// The screen dock space always covers the whole screen, stays behind floating windows
+    /// and keeps an empty central node. Every other dock space floats like a window.
+    pub screen: bool,
+}
+
+impl DockSpace {
+    pub fn screen() -> Self {
+        Self {
+            id: 0,
+            rect: Rect::default(),
+            root: DockNode::central(),
+            screen: true,
+        }
+    }
+
+    pub fn floating(id: DockSpaceId, rect: Rect, window: WindowId) -> Self {
+        Self {
+            id,
+            rect,
+            root: DockNode::leaf(vec![window]),
+            screen: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DockTarget {
+    Window(WindowId, DockSide),
+    Screen(DockSide),
+}
+
+/// What's being dragged. Dock spaces are held by id, since spaces before them can go away mid-drag.
+pub enum DockDrag {
+    Splitter {
+        space: DockSpaceId,
+        path: DockPath,
+    },
+    /// A tab was pressed. It gets pulled out of its dock once the mouse moves far enough.
+    Tab {
+        window: WindowId,
+        start: Vec2,
+    },
+    Move {
+        space: DockSpaceId,
+        offset: Vec2,
+    },
+    Resize {
+        space: DockSpaceId,
+    },
+}
+
+/// Drop targets and the preview of where the dragged window would go.
+pub struct DropOverlay {
+    /// Every target on screen, and whether the mouse is over it.
+    pub targets: Vec<(Rect, bool)>,
+    pub hovered: Option<(DockTarget, Rect)>,
+}
+
+/// All of `WindowHandler`'s docking state, recomputed by `layout` every frame.
+pub struct Docking {
+    /// The first one is always the screen dock space.
+    pub spaces: Vec<DockSpace>,
+    /// Visible leaves and splits from the last layout, with the index of their dock space.
+    pub leaves: Vec<(usize, DockLeaf)>,
+    pub splits: Vec<(usize, DockSplit)>,
+    pub window_docks: HashMap<WindowId, usize>,
+
+    pub drag: Option<DockDrag>,
+    pub hovered_split: Option<usize>,
+    /// The floating window being dragged, while it could still be dropped into a dock.
+    pub dragged_window: Option<WindowId>,
+    pub overlay: Option<DropOverlay>,
+    /// Dock spaces from a loaded layout. They're set up by the next layout, once that frame's windows exist.
+    pub pending: Option<Vec<DockLayout>>,
+    next_space_id: DockSpaceId,
+}
+
+impl Docking {
+    pub fn new() -> Self {
+        Self {
+            spaces: vec![DockSpace::screen()],
+            leaves: vec![],
+            splits: vec![],
+            window_docks: HashMap::new(),
+            drag: None,
+            hovered_split: None,
+            dragged_window: None,
+            overlay: None,
+            pending: None,
+            next_space_id: 1,
+        }
+    }
+
+    /// Adds a floating dock space holding `window`, and returns its index.
+    pub fn add_floating(&mut self, rect: Rect, window: WindowId) -> usize {
+        self.spaces.push(DockSpace::floating(self.next_space_id, rect, window));
+        self.next_space_id += 1;
+        self.spaces.len() - 1
+    }
+
+    /// Replaces every dock space with saved ones. Without a saved screen dock space, the screen is left empty.
+    pub fn load(&mut self, docks: &[DockLayout], id: &dyn Fn(&str) -> WindowId) {
+        self.spaces.truncate(1);
+        self.spaces[0].root = DockNode::central();
+        self.drag = None;
+
+        for dock in docks {
+            let root = DockNode::from_layout(&dock.root, id);
+
+            match dock.rect {
+                Some(rect) => {
+                    self.spaces.push(DockSpace {
+                        id: self.next_space_id,
+                        rect,
+                        root,
+                        screen: false,
+                    });
+                    self.next_space_id += 1;
+                }
+                _ => self.spaces[0].root = root,
+            }
+        }
+    }
+
+    /// Where the dock space with `id` is in `spaces`, if it's still there.
+    pub fn space_index(&self, id: DockSpaceId) -> Option<usize> {
+        self.spaces.iter().position(|space| space.id == id)
+    }
+
+    /// Where the split at `path` in dock space `id` is in `splits`, if it's still there.
+    pub fn split_index(&self, id: DockSpaceId, path: &DockPath) -> Option<usize> {
+        let dock = self.space_index(id)?;
+        self.splits
+            .iter()
+            .position(|(d, split)| *d == dock && split.path == *path)
+    }
+
+    /// The dock space and leaf path holding `id`.
+    pub fn find(&self, id: WindowId) -> Option<(usize, DockPath)> {
+        let dock = *self.window_docks.get(&id)?;
+        Some((dock, self.spaces[dock].root.find(id)?))
+    }
+
+    /// The leaf whose visible tab is `id`.
+    pub fn leaf_of(&self, id: WindowId) -> Option<&(usize, DockLeaf)> {
+        self.leaves
+            .iter()
+            .find(|(_, leaf)| leaf.windows.get(leaf.active) == Some(&id))
+    }
+}
+
+/// The five drop targets shown over a window while another window is dragged across it.
+pub fn window_drop_targets(rect: Rect) -> [(DockSide, Rect); 5] {
+    let size = 28.0;
+    let gap = 34.0;
+    let c = rect.center() - vec2(size, size) / 2.0;
+    let at = |offset: Vec2| Rect::new(c.x + offset.x, c.y + offset.y, size, size);
+
+    [
+        (DockSide::Center, at(Vec2::ZERO)),
+        (DockSide::Left, at(vec2(-gap, 0.0))),
+        (DockSide::Right, at(vec2(gap, 0.0))),
+        (DockSide::Top, at(vec2(0.0, -gap))),
+        (DockSide::Bottom, at(vec2(0.0, gap))),
+    ]
+}
+
+/// The drop targets along the screen edges, which dock into the screen dock space.
+pub fn screen_drop_targets(screen: Vec2) -> [(DockSide, Rect); 4] {
+    let size = 28.0;
+    let margin = 8.0;
+
+    [
+        (
+            DockSide::Left,
+            Rect::new(margin, (screen.y - size) / 2.0, size, size),
+        ),
+        (
+            DockSide::Right,
+            Rect::new(
+                screen.x - size - margin,
+                (screen.y - size) / 2.0,
+                size,
+                size,
+            ),
+        ),
+        (
+            DockSide::Top,
+            Rect::new((screen.x - size) / 2.0, margin, size, size),
+        ),
+        (
+            DockSide::Bottom,
+            Rect::new(
+                (screen.x - size) / 2.0,
+                screen.y - size - margin,
+                size,
+                size,
+            ),
+        ),
+    ]
+}
+
+/// The part of `rect` a window would take up if docked on `side`, taking `ratio` of the space.
+pub fn dock_preview(rect: Rect, side: DockSide, ratio: f32) -> Rect {
+    match side {
+        DockSide::Center => rect,
+        DockSide::Left => Rect::new(rect.x, rect.y, rect.w * ratio, rect.h),
+        DockSide::Right => Rect::new(
+            rect.right() - rect.w * ratio,
+            rect.y,
+            rect.w * ratio,
+            rect.h,
+        ),
+        DockSide::Top => Rect::new(rect.x, rect.y, rect.w, rect.h * ratio),
+        DockSide::Bottom => Rect::new(
+            rect.x,
+            rect.bottom() - rect.h * ratio,
+            rect.w,
+            rect.h * ratio,
+        ),
+    }
+}
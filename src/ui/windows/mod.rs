@@ -1,7 +1,10 @@
 pub mod action_type;
+pub(crate) mod dock;
+pub mod style;
 mod win_resize_handles;
 pub mod window;
 pub mod window_handler;
 mod window_info;
+pub(crate) mod window_layout;
 pub(crate) mod window_properties;
 pub mod window_theme;
@@ -0,0 +1,36 @@
+use crate::ui::windows::window_theme::{ThemeColor, WindowTheme};
+use macroquad::prelude::*;
+
+/// A theme change for the widgets created between `Window::push_style` and `Window::pop_style`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StyleVar {
+    Color(ThemeColor, Color),
+    /// Space after each widget.
+    Padding(f32),
+    FontSize(u16),
+    /// Corner radius of buttons and widget frames.
+    Rounding(f32),
+}
+
+impl StyleVar {
+    pub fn apply(&self, theme: &mut WindowTheme) {
+        match *self {
+            StyleVar::Color(which, color) => *theme.color_mut(which) = color,
+            StyleVar::Padding(padding) => theme.widget_padding = padding,
+            StyleVar::FontSize(size) => theme.font_size = size,
+            StyleVar::Rounding(radius) => {
+                theme.button_rounding = radius;
+                theme.frame_rounding = radius;
+            }
+        }
+    }
+}
+
+/// `theme` with every style in `styles` applied, later ones winning.
+pub(crate) fn styled_theme(theme: &WindowTheme, styles: &[StyleVar]) -> WindowTheme {
+    let mut theme = theme.clone();
+    for style in styles {
+        style.apply(&mut theme);
+    }
+    theme
+}
@@ -1,25 +1,69 @@
+use crate::ui::draw_list::DrawList;
+use crate::ui::input::UiInput;
 use crate::ui::windows::window_theme::WindowTheme;
 use macroquad::input::MouseButton::Left;
-use macroquad::miniquad::window::set_mouse_cursor;
 use macroquad::miniquad::CursorIcon;
 use macroquad::prelude::*;
 
-pub enum ResizeAxis {
-    X(f32, f32),
-    Y(f32, f32),
-    XY(f32, f32, f32, f32),
-}
-
+#[derive(Clone, Copy, PartialEq)]
 pub enum ResizeHandle {
+    Left,
     Right,
+    Top,
     Bottom,
-    Corner,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ResizeHandle {
+    /// Corners first, so they win over the edges they overlap.
+    const ALL: [ResizeHandle; 8] = [
+        ResizeHandle::TopLeft,
+        ResizeHandle::TopRight,
+        ResizeHandle::BottomLeft,
+        ResizeHandle::BottomRight,
+        ResizeHandle::Left,
+        ResizeHandle::Right,
+        ResizeHandle::Top,
+        ResizeHandle::Bottom,
+    ];
+
+    /// Which side of the window each axis moves: -1 for left/top, 1 for right/bottom, 0 for neither.
+    fn sides(self) -> (i8, i8) {
+        match self {
+            ResizeHandle::Left => (-1, 0),
+            ResizeHandle::Right => (1, 0),
+            ResizeHandle::Top => (0, -1),
+            ResizeHandle::Bottom => (0, 1),
+            ResizeHandle::TopLeft => (-1, -1),
+            ResizeHandle::TopRight => (1, -1),
+            ResizeHandle::BottomLeft => (-1, 1),
+            ResizeHandle::BottomRight => (1, 1),
+        }
+    }
+
+    fn cursor(self) -> CursorIcon {
+        match self {
+            ResizeHandle::Left | ResizeHandle::Right => CursorIcon::EWResize,
+            ResizeHandle::Top | ResizeHandle::Bottom => CursorIcon::NSResize,
+            ResizeHandle::TopLeft | ResizeHandle::BottomRight => CursorIcon::NWSEResize,
+            _ => CursorIcon::NESWResize,
+        }
+    }
+}
+
+pub struct ResizeDrag {
+    pub handle: ResizeHandle,
+    pub start_mouse: Vec2,
+    pub start_rect: Rect,
 }
 
 pub struct WindowResizeHandles {
     pub thickness: f32,
     pub corner_size: f32,
-    pub resizing: Option<ResizeAxis>,
+    pub resizing: Option<ResizeDrag>,
     pub hovering_handle: Option<ResizeHandle>,
     pub opacity: f32,
 }
@@ -35,69 +79,82 @@ impl WindowResizeHandles {
         }
     }
 
-    pub fn render(&self, rect: &Rect, theme: &WindowTheme) {
+    pub fn render(&self, rect: &Rect, theme: &WindowTheme, draw_list: &mut DrawList) {
         let mut color = theme.resize_handle.to_vec();
         color.w *= self.opacity;
 
         let color = Color::from_vec(color);
 
-        match self.hovering_handle {
-            Some(ResizeHandle::Right) => {
-                // RIGHT HANDLE
-                draw_line(
-                    rect.x + rect.w,
-                    rect.y,
-                    rect.x + rect.w,
-                    rect.y + rect.h,
-                    self.thickness,
-                    color,
-                );
-                set_mouse_cursor(CursorIcon::EWResize);
-            }
-
-            Some(ResizeHandle::Bottom) => {
-                // BOTTOM HANDLE
-                draw_line(
-                    rect.x,
-                    rect.y + rect.h,
-                    rect.x + rect.w,
-                    rect.y + rect.h,
-                    self.thickness,
-                    color,
-                );
-                set_mouse_cursor(CursorIcon::NSResize);
-            }
-
-            Some(ResizeHandle::Corner) => {
-                // CORNER HANDLE
-                draw_rectangle(
-                    rect.x + rect.w - self.corner_size / 2.0,
-                    rect.y + rect.h - self.corner_size / 2.0,
-                    self.corner_size,
-                    self.corner_size,
-                    color,
-                );
-                set_mouse_cursor(CursorIcon::NWSEResize);
-            }
-
-            _ => {}
+        let Some(handle) = self.hovering_handle else {
+            return;
+        };
+
+        let (sx, sy) = handle.sides();
+        let x = match sx {
+            -1 => rect.x,
+            _ => rect.x + rect.w,
+        };
+        let y = match sy {
+            -1 => rect.y,
+            _ => rect.y + rect.h,
+        };
+
+        match (sx, sy) {
+            // EDGES
+            (_, 0) => draw_list.draw_line(x, rect.y, x, rect.y + rect.h, self.thickness, color),
+            (0, _) => draw_list.draw_line(rect.x, y, rect.x + rect.w, y, self.thickness, color),
+
+            // CORNERS
+            _ => draw_list.draw_rectangle(
+                x - self.corner_size / 2.0,
+                y - self.corner_size / 2.0,
+                self.corner_size,
+                self.corner_size,
+                color,
+            ),
         }
     }
 
-    pub fn update(&mut self, rect: &mut Rect, hover: bool, taken: bool) {
-        let mouse: Vec2 = mouse_position().into();
+    /// The cursor for the handle being hovered or dragged, if there is one.
+    pub fn cursor(&self) -> Option<CursorIcon> {
+        self.hovering_handle.map(ResizeHandle::cursor)
+    }
+
+    /// Resizes `rect` by whichever handle is being dragged, keeping the opposite edge in place
+    /// and the size between `min` and `max`.
+    pub fn update(
+        &mut self,
+        rect: &mut Rect,
+        hover: bool,
+        taken: bool,
+        input: &UiInput,
+        min: Vec2,
+        max: Vec2,
+    ) {
+        let mouse = input.mouse;
 
         if self.resizing.is_none() {
             self.hovering_handle = None;
-        }
 
-        if hover && !taken {
-            self.corner_handle(mouse, rect);
-            self.right_handle(mouse, rect);
-            self.bottom_handle(mouse, rect);
+            if hover && !taken {
+                self.hovering_handle = ResizeHandle::ALL
+                    .into_iter()
+                    .find(|h| self.handle_rect(*h, rect).contains(mouse));
+
+                if let (Some(handle), true) = (
+                    self.hovering_handle,
+                    input.is_mouse_button_pressed(Left),
+                ) {
+                    self.resizing = Some(ResizeDrag {
+                        handle,
+                        start_mouse: mouse,
+                        start_rect: *rect,
+                    });
+                }
+            }
         }
 
-        if is_mouse_button_released(Left) {
+        if input.is_mouse_button_released(Left) {
             self.resizing = None;
         }
 
@@ -107,72 +164,62 @@ impl WindowResizeHandles {
             self.opacity = self.opacity.lerp(0.0, 0.1);
         }
 
-        match &self.resizing {
-            &Some(ResizeAxis::X(start, o_width)) => {
-                rect.w = o_width + (mouse.x - start);
-            }
-
-            &Some(ResizeAxis::Y(start, o_height)) => {
-                rect.h = o_height + (mouse.y - start);
+        let Some(drag) = &self.resizing else {
+            return;
+        };
+
+        let delta = mouse - drag.start_mouse;
+        let start = drag.start_rect;
+        let (sx, sy) = drag.handle.sides();
+
+        // The window can't grow past the screen edge it's being dragged towards
+        match sx {
+            1 => {
+                rect.w = (start.w + delta.x)
+                    .min(max.x)
+                    .min(input.screen_size.x - start.x)
+                    .max(min.x);
             }
-
-            &Some(ResizeAxis::XY(sx, sy, ow, oh)) => {
-                rect.w = ow + (mouse.x - sx);
-                rect.h = oh + (mouse.y - sy);
+            -1 => {
+                rect.w = (start.w - delta.x).min(max.x).min(start.right()).max(min.x);
+                rect.x = start.right() - rect.w;
             }
-
             _ => {}
         }
-    }
-
-    pub fn right_handle(&mut self, mouse: Vec2, rect: &mut Rect) {
-        let handle = Rect::new(
-            rect.x + rect.w - self.thickness / 2.0,
-            rect.y,
-            self.thickness,
-            rect.h,
-        );
-
-        if handle.contains(mouse) && self.resizing.is_none() {
-            self.hovering_handle = Some(ResizeHandle::Right);
 
-            if is_mouse_button_pressed(Left) {
-                self.resizing = Some(ResizeAxis::X(mouse.x, rect.w))
+        match sy {
+            1 => {
+                rect.h = (start.h + delta.y)
+                    .min(max.y)
+                    .min(input.screen_size.y - start.y)
+                    .max(min.y);
             }
-        }
-    }
-
-    pub fn bottom_handle(&mut self, mouse: Vec2, rect: &mut Rect) {
-        let handle = Rect::new(
-            rect.x,
-            rect.y + rect.h - self.thickness / 2.0,
-            rect.w,
-            self.thickness,
-        );
-
-        if handle.contains(mouse) && self.resizing.is_none() {
-            self.hovering_handle = Some(ResizeHandle::Bottom);
-
-            if is_mouse_button_pressed(Left) {
-                self.resizing = Some(ResizeAxis::Y(mouse.y, rect.h))
+            -1 => {
+                rect.h = (start.h - delta.y).min(max.y).min(start.bottom()).max(min.y);
+                rect.y = start.bottom() - rect.h;
             }
+            _ => {}
         }
     }
 
-    pub fn corner_handle(&mut self, mouse: Vec2, rect: &mut Rect) {
-        let handle = Rect::new(
-            rect.x + rect.w - self.corner_size / 2.0,
-            rect.y + rect.h - self.corner_size / 2.0,
-            self.corner_size,
-            self.corner_size,
-        );
-
-        if handle.contains(mouse) && self.resizing.is_none() {
-            self.hovering_handle = Some(ResizeHandle::Corner);
-
-            if is_mouse_button_pressed(Left) {
-                self.resizing = Some(ResizeAxis::XY(mouse.x, mouse.y, rect.w, rect.h))
-            }
+    fn handle_rect(&self, handle: ResizeHandle, rect: &Rect) -> Rect {
+        let (sx, sy) = handle.sides();
+        let t = self.thickness;
+        let c = self.corner_size;
+
+        let edge_x = |side| match side {
+            -1 => rect.x,
+            _ => rect.x + rect.w,
+        };
+        let edge_y = |side| match side {
+            -1 => rect.y,
+            _ => rect.y + rect.h,
+        };
+
+        match (sx, sy) {
+            (_, 0) => Rect::new(edge_x(sx) - t / 2.0, rect.y, t, rect.h),
+            (0, _) => Rect::new(rect.x, edge_y(sy) - t / 2.0, rect.w, t),
+            _ => Rect::new(edge_x(sx) - c / 2.0, edge_y(sy) - c / 2.0, c, c),
         }
     }
 }
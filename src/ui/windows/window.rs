@@ -1,19 +1,28 @@
-use crate::misc::rounded_rect::{draw_rounded_rect_ex, draw_rounded_rect_stroke};
 use crate::text_ex::TextEx;
+use crate::ui::draw_list::DrawList;
+use crate::ui::focus::FocusContext;
+use crate::ui::fonts::FontStyle;
+use crate::ui::input::UiInput;
 use crate::ui::mouse_action::{MouseAction, WidgetAction};
+use crate::ui::windows::style::StyleVar;
 use crate::ui::windows::win_resize_handles::WindowResizeHandles;
 use crate::ui::windows::window_info::WindowInfo;
-use crate::widget_holder::{GlobalRenderTargets, WidgetHolder};
+use crate::ui::windows::window_layout::WindowLayout;
+use crate::widget_holder::{WidgetHolder, WidgetIdNum};
 use crate::widgets::*;
-use crate::{ActionType, WindowId, WindowProperties, WindowTheme};
+use crate::{ActionType, Error, WindowId, WindowProperties, WindowTheme};
 use indexmap::IndexSet;
 use macroquad::input::MouseButton::Left;
+use macroquad::miniquad::CursorIcon;
 use macroquad::prelude::*;
 use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash, Hasher};
+use std::rc::Rc;
 
 pub struct Window {
     pub id: WindowId,
+    /// The id string passed to `UI::begin`.
+    pub name: String,
     pub title: String,
     pub rect: Rect,
     pub widget_holders: HashMap<String, WidgetHolder>, // Changed to HashMap
@@ -24,16 +33,38 @@ pub struct Window {
     resize_handles: WindowResizeHandles,
     pub scroll_y: f32,
     pub max_scroll_y: f32,
+    pub scroll_x: f32,
+    pub max_scroll_x: f32,
     pub scrolling: bool,
-    render_targets: GlobalRenderTargets,
 
     mouse: Vec2,
+    screen_size: Vec2,
+    /// The mouse cursor the window or one of its widgets asked for this frame.
+    cursor: Option<CursorIcon>,
+    /// `UiInput::time` from the last update.
+    time: f64,
     pub open: bool,
     pub active: bool,
     pub hover: bool,
     pub mouse_action: WidgetAction,
     pub dragging: Option<Vec2>,
     pub resizing: bool,
+
+    /// Focusable widgets and their rects from the last update, in focus order.
+    pub(crate) focus_items: Vec<(WidgetIdNum, Rect)>,
+    /// The widget to draw the focus ring around, if any.
+    pub(crate) focus_ring: Option<WidgetIdNum>,
+
+    /// Docked windows get their rect from the dock, and the handler draws their tab bar.
+    pub(crate) docked: bool,
+    /// Docked behind another tab, so not updated or drawn.
+    pub(crate) dock_hidden: bool,
+
+    /// Pushed with `push_style`, and applied to every widget created while it's on the stack.
+    style_stack: Rc<Vec<StyleVar>>,
+    pub(crate) id_policy: IdConflictPolicy,
+    /// Pushed with `push_id`. Each entry already has the ones below it mixed in.
+    id_stack: Vec<u64>,
 }
 
 impl Window {
@@ -42,7 +73,8 @@ impl Window {
             theme,
             id,
             taken: false,
-            title: name,
+            title: name.clone(),
+            name,
             rect: Rect::new(0.0, 0.0, 200.0, 150.0),
             info: WindowInfo::new(),
             resize_handles: WindowResizeHandles::new(),
@@ -50,16 +82,30 @@ impl Window {
             holder_ids: IndexSet::from([String::from("__Main__")]),
             scroll_y: 0.0,
             max_scroll_y: 0.0,
+            scroll_x: 0.0,
+            max_scroll_x: 0.0,
             scrolling: false,
-            render_targets: GlobalRenderTargets::default(),
 
             open: true,
-            mouse: mouse_position().into(),
+            mouse: Vec2::ZERO,
+            screen_size: Vec2::ZERO,
+            cursor: None,
+            time: 0.0,
             active: true,
             hover: false,
             dragging: None,
             resizing: false,
             mouse_action: WidgetAction::new(),
+
+            focus_items: vec![],
+            focus_ring: None,
+
+            docked: false,
+            dock_hidden: false,
+
+            style_stack: Rc::default(),
+            id_policy: IdConflictPolicy::default(),
+            id_stack: vec![],
         }
     }
 
@@ -78,10 +124,11 @@ impl Window {
         position: Vec2,
         action_type: impl Into<ActionType> + Clone,
     ) -> &mut Window {
-        if action_type.clone().into() == ActionType::Once && !self.info.ran_once {
-            self.rect.x = position.x;
-            self.rect.y = position.y;
-        } else if action_type.into() == ActionType::EachFrame {
+        if self.docked {
+            return self;
+        }
+
+        if action_type.into() == ActionType::EachFrame || self.first_frame() {
             self.rect.x = position.x;
             self.rect.y = position.y;
         }
@@ -94,17 +141,59 @@ impl Window {
         size: Vec2,
         action_type: impl Into<ActionType> + Clone,
     ) -> &mut Window {
-        if action_type.clone().into() == ActionType::Once && !self.info.ran_once {
-            self.rect.w = size.x;
-            self.rect.h = size.y;
-        } else if action_type.into() == ActionType::EachFrame {
+        if self.docked {
+            return self;
+        }
+
+        if action_type.into() == ActionType::EachFrame || self.first_frame() {
             self.rect.w = size.x;
-            self.rect.h = size.y;
+            match self.info.collapsed {
+                true => self.info.expanded_height = size.y,
+                _ => self.rect.h = size.y,
+            }
+        }
+
+        self
+    }
+
+    /// Shrinks the window down to its titlebar, or expands it back to the height it had before.
+    pub fn set_collapsed(
+        &mut self,
+        collapsed: bool,
+        action_type: impl Into<ActionType> + Clone,
+    ) -> &mut Window {
+        if action_type.into() == ActionType::EachFrame || self.first_frame() {
+            self.apply_collapsed(collapsed);
         }
 
         self
     }
 
+    pub fn is_collapsed(&self) -> bool {
+        self.info.collapsed
+    }
+
+    pub(crate) fn apply_collapsed(&mut self, collapsed: bool) {
+        // Without a titlebar (or while docked) there'd be nothing left to click to expand it again
+        let can_collapse = self.info.show_titlebar && !self.docked;
+        if collapsed == self.info.collapsed || (collapsed && !can_collapse) {
+            return;
+        }
+
+        if collapsed {
+            self.info.expanded_height = self.rect.h;
+            self.rect.h = self.collapsed_height();
+        } else {
+            self.rect.h = self.info.expanded_height.max(self.collapsed_height());
+        }
+        self.info.collapsed = collapsed;
+        self.info.last_title_click = None;
+    }
+
+    fn collapsed_height(&self) -> f32 {
+        self.theme.title_thickness + self.theme.border_radius / 2.0 - 1.0
+    }
+
     pub fn set_properties(&mut self, properties: WindowProperties) -> &mut Window {
         self.info.draggable = properties.draggable;
         self.info.resizable = properties.resizable;
@@ -143,6 +232,16 @@ impl Window {
         self
     }
 
+    /// Whether the window can be dropped into a dock (true by default).
+    pub fn set_dockable(&mut self, dockable: bool) -> &mut Window {
+        self.info.dockable = dockable;
+        self
+    }
+
+    pub fn is_docked(&self) -> bool {
+        self.docked
+    }
+
     pub fn set_active(&mut self, active: bool) -> &mut Window {
         self.active = active;
         self
@@ -164,10 +263,34 @@ impl Window {
         self
     }
 
+    // `ActionType::Once` only applies on the first frame, and not at all if a saved layout placed the window
+    fn first_frame(&self) -> bool {
+        !self.info.ran_once && !self.info.layout_loaded
+    }
+
+    pub(crate) fn set_theme(&mut self, theme: WindowTheme) {
+        self.theme = theme;
+
+        // The titlebar might have changed height
+        if self.info.collapsed {
+            self.rect.h = self.collapsed_height();
+        }
+    }
+
+    pub(crate) fn cursor(&self) -> Option<CursorIcon> {
+        self.cursor
+    }
+
     pub fn set_min_size(&mut self, size: Vec2) -> &mut Window {
         self.info.min_size = size;
         self
     }
+
+    /// The largest size the window can be resized to. The min size wins if they overlap.
+    pub fn set_max_size(&mut self, size: Vec2) -> &mut Window {
+        self.info.max_size = size;
+        self
+    }
 }
 
 /////////////////////////////////////
@@ -175,7 +298,7 @@ impl Window {
 /////////////////////////////////////
 
 impl Window {
-    pub fn render(&self) {
+    pub fn render(&self, draw_list: &mut DrawList) {
         // BASE
         // draw_rectangle(
         //     self.rect.x,
@@ -185,7 +308,7 @@ impl Window {
         //     self.theme.background,
         // );
 
-        draw_rounded_rect_stroke(
+        draw_list.draw_rounded_rect_stroke(
             self.rect.x,
             self.rect.y,
             self.rect.w,
@@ -200,142 +323,103 @@ impl Window {
             self.theme.background,
         );
 
-        self.start_widget_render();
+        // Dropdowns and other popups draw into the overlay, which goes on top of the whole window
+        let mut overlay = DrawList::new();
+        if !self.info.collapsed {
+            self.render_widgets(draw_list, &mut overlay);
+            self.draw_focus_ring(draw_list);
 
-        self.draw_scrollbar();
-
-        // TITLEBAR
-        if self.info.show_titlebar {
-            self.draw_titlebar();
+            self.draw_scrollbar(draw_list);
+            self.draw_h_scrollbar(draw_list);
         }
 
-        if !self.info.scroll_hovered && !self.info.scroll_pressed.is_some() {
-            self.draw_resize_handles();
+        // TITLEBAR (docked windows get a tab bar from the handler instead)
+        if self.info.show_titlebar && !self.docked {
+            self.draw_titlebar(draw_list);
         }
 
-        if let Some(target) = &self.render_targets.target_3 {
-            draw_texture_ex(
-                &target.texture,
-                self.rect.x + 5.0,
-                self.rect.y + self.theme.title_thickness,
-                WHITE,
-                DrawTextureParams {
-                    dest_size: Some(vec2(screen_width(), screen_height())),
-                    ..Default::default()
-                },
-            );
+        if !self.docked
+            && !self.info.collapsed
+            && !self.on_scrollbar()
+        {
+            self.draw_resize_handles(draw_list);
         }
-    }
 
-    pub fn start_widget_render(&self) {
-        let scale = 0.01;
+        draw_list.append(&mut overlay);
+    }
 
+    fn content_rect(&self) -> Rect {
         let title_thickness = match self.info.show_titlebar {
             false => 0.0,
-            _ => 30.0,
-        };
-        let (zoom_x, zoom_y) = (
-            scale / self.rect.w * 200.0,
-            scale / (self.rect.h - title_thickness) * 200.0,
-        );
-
-        let cam_1 = &Camera2D {
-            zoom: vec2(zoom_x, zoom_y),
-            target: vec2(1.0 / zoom_x, 1.0 / zoom_y),
-            render_target: Some(self.render_targets.target_1.clone().unwrap()),
-            ..Default::default()
-        };
-        let cam_2 = &Camera2D {
-            zoom: vec2(zoom_x, zoom_y),
-            target: vec2(1.0 / zoom_x, 1.0 / zoom_y),
-            render_target: Some(self.render_targets.target_2.clone().unwrap()),
-            ..Default::default()
-        };
-        let cam_3 = &Camera2D {
-            zoom: vec2(
-                scale / screen_width() * 200.0,
-                scale / screen_height() * 200.0,
-            ),
-            target: vec2(
-                1.0 / scale * screen_width() / 200.0,
-                1.0 / scale * screen_height() / 200.0,
-            ),
-            render_target: Some(self.render_targets.target_3.clone().unwrap()),
-            ..Default::default()
+            _ => self.theme.title_thickness,
         };
 
-        set_camera(cam_3);
-        clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+        Rect::new(
+            self.rect.x + 5.0,
+            self.rect.y + title_thickness,
+            (self.rect.w - 5.0).max(0.0),
+            (self.rect.h - 5.0 - title_thickness).max(0.0),
+        )
+    }
 
-        set_camera(cam_2);
-        clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+    pub fn render_widgets(&self, draw_list: &mut DrawList, overlay: &mut DrawList) {
+        // Widgets draw relative to the top-left of the content area and get clipped to it
+        let content = self.content_rect();
 
-        set_camera(cam_1);
-        clear_background(Color::new(0.0, 0.0, 0.0, 0.0));
+        draw_list.set_clip(Some(content));
+        draw_list.set_offset(content.point());
+        overlay.set_offset(content.point());
 
         // DRAW TOP-LAYER OF WIDGETS
-        let mut new_rect = self.rect.clone();
+        let mut new_rect = self.rect;
         new_rect.x *= 0.0;
         new_rect.y *= 0.0;
-        
+
         let mut vertical_offset = 0.0;
 
         for i in self.holder_ids.iter() {
-            set_camera(cam_1);
             let holder = self.widget_holders.get(i).unwrap();
 
             let (_, rect_h) = holder.render(
                 &new_rect,
-                self.scroll_y,
-                &self.theme.font,
+                vec2(self.scroll_x, self.scroll_y),
+                &self.theme,
                 vertical_offset,
-                [&cam_1, &cam_2, &cam_3],
+                self.time,
+                draw_list,
+                overlay,
             );
 
             vertical_offset += rect_h + self.theme.holder_padding;
         }
 
-        set_default_camera();
-
-        if let Some(target) = &self.render_targets.target_1 {
-            draw_texture_ex(
-                &target.texture,
-                self.rect.x + 5.0,
-                self.rect.y + title_thickness,
-                WHITE,
-                DrawTextureParams {
-                    source: Some(Rect::new(
-                        0.0,
-                        0.0,
-                        (self.rect.w - 5.0).max(0.0),
-                        (self.rect.h - 5.0 - title_thickness).max(0.0),
-                    )),
-                    ..Default::default()
-                },
-            )
-        }
-
-        if let Some(target) = &self.render_targets.target_2 {
-            draw_texture_ex(
-                &target.texture,
-                self.rect.x + 5.0,
-                self.rect.y + title_thickness,
-                WHITE,
-                DrawTextureParams {
-                    source: Some(Rect::new(
-                        0.0,
-                        0.0,
-                        (self.rect.w - 5.0).max(0.0),
-                        (self.rect.h - 5.0 - title_thickness).max(0.0),
-                    )),
-                    ..Default::default()
-                },
-            )
-        }
+        draw_list.set_offset(Vec2::ZERO);
+        draw_list.set_clip(None);
+        overlay.set_offset(Vec2::ZERO);
+    }
+
+    pub fn draw_focus_ring(&self, draw_list: &mut DrawList) {
+        let Some(focused) = self.focus_ring else {
+            return;
+        };
+        let Some((_, rect)) = self.focus_items.iter().find(|(id, _)| *id == focused) else {
+            return;
+        };
+
+        draw_list.set_clip(Some(self.content_rect()));
+        draw_list.draw_rectangle_lines(
+            rect.x - 2.0,
+            rect.y - 2.0,
+            rect.w + 4.0,
+            rect.h + 4.0,
+            2.0,
+            self.theme.focus_ring,
+        );
+        draw_list.set_clip(None);
     }
 
-    pub fn draw_titlebar(&self) {
-        draw_rounded_rect_ex(
+    pub fn draw_titlebar(&self, draw_list: &mut DrawList) {
+        draw_list.draw_rounded_rect_ex(
             self.rect.x + 1.0,
             self.rect.y + 1.0,
             self.rect.w - 2.0,
@@ -350,27 +434,70 @@ impl Window {
             },
         );
 
-        draw_text_ex(
+        self.draw_collapse_button(draw_list);
+
+        draw_list.draw_text_ex(
             &self.title,
-            self.rect.x + 10.0,
+            self.rect.x + self.theme.title_thickness,
             self.rect.y + self.theme.title_thickness - 10.0,
             TextParams {
-                font: match &self.theme.font {
-                    Some(f) => Some(&f),
-                    _ => None,
-                },
-                font_size: 13,
+                font: self.theme.font_for(FontStyle::Bold),
+                font_size: self.theme.title_font_size,
+                color: self.theme.title_text,
                 ..Default::default()
             },
         );
 
         if self.info.closable {
-            self.draw_close_button();
+            self.draw_close_button(draw_list);
         }
     }
 
-    pub fn draw_close_button(&self) {
-        draw_rounded_rect_ex(
+    fn collapse_button_rect(&self) -> Rect {
+        Rect::new(
+            self.rect.x + 4.0,
+            self.rect.y + 4.0,
+            self.theme.title_thickness - 8.0,
+            self.theme.title_thickness - 8.0,
+        )
+    }
+
+    pub fn draw_collapse_button(&self, draw_list: &mut DrawList) {
+        let rect = self.collapse_button_rect();
+
+        if self.info.collapse_hovered {
+            draw_list.draw_rounded_rect(
+                rect.x,
+                rect.y,
+                rect.w,
+                rect.h,
+                self.theme.border_radius - 2.0,
+                self.theme.title_button_hover,
+            );
+        }
+
+        // Points right when collapsed, down when expanded
+        let c = rect.center();
+        let s = 4.0;
+        let (a, b, tip) = match self.info.collapsed {
+            true => (
+                vec2(c.x - s / 2.0, c.y - s),
+                vec2(c.x - s / 2.0, c.y + s),
+                vec2(c.x + s / 2.0, c.y),
+            ),
+            _ => (
+                vec2(c.x - s, c.y - s / 2.0),
+                vec2(c.x + s, c.y - s / 2.0),
+                vec2(c.x, c.y + s / 2.0),
+            ),
+        };
+
+        draw_list.draw_line(a.x, a.y, tip.x, tip.y, 1.5, self.theme.title_text);
+        draw_list.draw_line(b.x, b.y, tip.x, tip.y, 1.5, self.theme.title_text);
+    }
+
+    pub fn draw_close_button(&self, draw_list: &mut DrawList) {
+        draw_list.draw_rounded_rect_ex(
             self.rect.x + self.rect.w - self.theme.title_thickness,
             self.rect.y + 1.0,
             self.theme.title_thickness - 1.0,
@@ -382,7 +509,7 @@ impl Window {
             self.info.close_color,
         );
 
-        draw_text_ex(
+        draw_list.draw_text_ex(
             "x",
             self.rect.x + self.rect.w - self.theme.title_thickness
                 + self.theme.title_thickness / 2.0
@@ -390,20 +517,39 @@ impl Window {
             self.rect.y + self.theme.title_thickness - 10.0,
             TextParams {
                 font: match &self.theme.font {
-                    Some(f) => Some(&f),
+                    Some(f) => Some(f),
                     _ => None,
                 },
-                font_size: 14,
+                font_size: self.theme.title_font_size + 1,
+                color: self.theme.title_text,
                 ..Default::default()
             },
         );
     }
 
-    pub fn draw_resize_handles(&self) {
-        self.resize_handles.render(&self.rect, &self.theme);
+    pub fn draw_resize_handles(&self, draw_list: &mut DrawList) {
+        self.resize_handles.render(&self.rect, &self.theme, draw_list);
     }
 
-    pub fn draw_scrollbar(&self) {
+    pub fn draw_h_scrollbar(&self, draw_list: &mut DrawList) {
+        let Some(bar) = self.h_scrollbar_rect() else {
+            return;
+        };
+
+        draw_list.draw_rectangle(
+            bar.x,
+            bar.y,
+            bar.w,
+            bar.h,
+            match (self.info.hscroll_hovered, self.info.hscroll_pressed.is_some()) {
+                (true, false) => self.theme.scrollbar_hover,
+                (_, true) => self.theme.scrollbar_press,
+                _ => self.theme.scrollbar,
+            },
+        );
+    }
+
+    pub fn draw_scrollbar(&self, draw_list: &mut DrawList) {
         if self.max_scroll_y < 5.0 {
             return;
         }
@@ -426,15 +572,15 @@ impl Window {
             self.rect.y + self.theme.title_thickness
         };
 
-        draw_rectangle(
+        draw_list.draw_rectangle(
             self.rect.x + self.rect.w - thickness,
             thumb_y,
             thickness,
             thumb_h,
             match (self.info.scroll_hovered, self.info.scroll_pressed.is_some()) {
-                (true, false) => WHITE.with_alpha(0.4),
-                (_, true) => WHITE.with_alpha(0.6),
-                _ => WHITE.with_alpha(0.2),
+                (true, false) => self.theme.scrollbar_hover,
+                (_, true) => self.theme.scrollbar_press,
+                _ => self.theme.scrollbar,
             },
         );
     }
@@ -445,18 +591,27 @@ impl Window {
 /////////////////////////////////////
 
 impl Window {
-    pub fn update(&mut self, active_taken: bool, mouse_action: MouseAction) {
+    pub fn update(
+        &mut self,
+        active_taken: bool,
+        mouse_action: MouseAction,
+        input: &UiInput,
+        focus: &mut FocusContext,
+    ) {
         if active_taken {
             self.active = false;
         }
-        self.mouse = mouse_position().into();
+        self.mouse = input.mouse;
+        self.screen_size = input.screen_size;
+        self.time = input.time;
+        self.cursor = None;
 
         let hover = self.rect.contains(self.mouse);
         let window_action = mouse_action == MouseAction::WindowHover(self.id)
             || mouse_action == MouseAction::Normal;
         self.hover = window_action && hover;
 
-        if is_mouse_button_pressed(Left) && window_action {
+        if input.is_mouse_button_pressed(Left) && window_action {
             if !active_taken {
                 self.active = hover;
             } else {
@@ -467,56 +622,75 @@ impl Window {
         self.taken = false;
         let mut mouse_action = WidgetAction::new();
         let mut scroll_hov = false;
-        if self.info.scroll_hovered {
+        if self.info.scroll_hovered || self.info.hscroll_hovered {
             mouse_action.taken = true;
             scroll_hov = true;
         }
 
+        if self.info.show_titlebar && !self.docked {
+            self.handle_collapse(window_action, input);
+        }
+
         let mut widget_action = WidgetAction::new();
         let mut vertical_offset = -self.theme.holder_padding;
+        let mut content_width: f32 = 0.0;
 
-        if window_action {
-            let new_rect = self.rect.clone();
+        if window_action && !self.info.collapsed {
+            let new_rect = self.rect;
 
             for i in self.holder_ids.iter() {
                 let holder = self.widget_holders.get_mut(i).unwrap();
 
+                let mut hasher = DefaultHasher::new();
+                i.hash(&mut hasher);
+                holder.focus_scope = hasher.finish();
+
                 let (action, holder_rect) = holder.update(
                     &new_rect,
                     vertical_offset,
                     self.info.show_titlebar,
                     hover,
-                    self.mouse,
-                    self.scroll_y,
-                    &self.theme.font,
+                    vec2(self.scroll_x, self.scroll_y),
+                    &self.theme,
                     &mut mouse_action,
+                    input,
+                    focus,
                 );
 
                 widget_action = action;
                 vertical_offset += holder_rect.h + self.theme.holder_padding;
+                content_width = content_width.max(holder_rect.w);
             }
+
+            self.focus_items = focus.items.clone();
+            self.cursor = mouse_action.cursor;
         }
 
-        if widget_action.taken && !scroll_hov {
+        if self.info.collapsed {
+            self.focus_items.clear();
+            self.info.scroll_hovered = false;
+            self.info.hscroll_hovered = false;
+        } else if widget_action.taken && !scroll_hov {
             self.taken = true;
         } else {
-            self.handle_scrolling(vertical_offset);
+            self.handle_scrolling(vertical_offset, content_width, input);
         }
 
-        if self.info.resizable {
-            self.update_resize_handles(window_action, self.taken || self.scrolling);
+        if self.info.resizable && !self.docked && !self.info.collapsed {
+            self.update_resize_handles(window_action, self.taken || self.scrolling, input);
         }
         if self.resizing || self.scrolling {
             self.taken = true;
         } else if self.info.closable {
-            self.handle_close_button(window_action);
+            self.handle_close_button(window_action, input);
         }
         if self.resize_handles.hovering_handle.is_some() {
             self.taken = true;
+            self.cursor = self.resize_handles.cursor();
         }
 
-        if self.info.draggable {
-            self.handle_dragging();
+        if self.info.draggable && !self.docked {
+            self.handle_dragging(input);
         }
 
         if let Some(start_offset) = self.dragging {
@@ -528,80 +702,107 @@ impl Window {
 
         self.rect.x = self.rect.x.floor();
         self.rect.y = self.rect.y.floor();
+    }
 
-        self.ensure_render_targets(match self.info.show_titlebar {
-            false => 0.0,
-            _ => self.theme.title_thickness,
-        });
+    fn handle_collapse(&mut self, window_action: bool, input: &UiInput) {
+        let mut title_rect = self.rect;
+        title_rect.h = self.theme.title_thickness;
+
+        let on_button = self.collapse_button_rect().contains(self.mouse);
+        self.info.collapse_hovered = window_action && on_button;
+
+        if !window_action
+            || !input.is_mouse_button_pressed(Left)
+            || !title_rect.contains(self.mouse)
+        {
+            return;
+        }
+
+        if on_button {
+            self.apply_collapsed(!self.info.collapsed);
+        } else if !self.info.close_button_rect.contains(self.mouse) {
+            match self.info.last_title_click {
+                Some(last) if input.time - last < 0.3 => {
+                    self.apply_collapsed(!self.info.collapsed)
+                }
+                _ => self.info.last_title_click = Some(input.time),
+            }
+        }
     }
 
-    fn handle_dragging(&mut self) {
-        let mut title_rect = self.rect.clone();
+    fn handle_dragging(&mut self, input: &UiInput) {
+        let mut title_rect = self.rect;
         title_rect.h = self.theme.title_thickness;
 
         if self.active
             && !self.resizing
             && !self.info.close_button_pressed
-            && is_mouse_button_pressed(Left)
+            && input.is_mouse_button_pressed(Left)
             && title_rect.contains(self.mouse)
         {
             self.dragging = Some(vec2(self.rect.x, self.rect.y) - self.mouse);
         }
 
-        if is_mouse_button_released(Left) {
+        if input.is_mouse_button_released(Left) {
             self.dragging = None;
         }
     }
 
-    pub fn clamp(&mut self) {
-        // CLAMP SIZE
-
-        if self.rect.h < self.theme.title_thickness + self.theme.border_radius / 2.0 - 1.0 {
-            self.rect.h = self.theme.title_thickness + self.theme.border_radius / 2.0 - 1.0;
-        }
-
-        let title_dim = measure_text(
+    /// The smallest the window can get: room for the title, and the user's `min_size`.
+    fn min_size(&self) -> Vec2 {
+        let title_dim = self.theme.fonts.measure_text(
             &self.title,
-            match &self.theme.font {
-                Some(f) => Some(&f),
-                _ => None,
-            },
-            14,
+            self.theme.font_for(FontStyle::Bold),
+            self.theme.title_font_size,
             1.0,
         );
-        if self.info.show_titlebar {
-            if self.rect.w < title_dim.width.max(self.info.close_button_rect.w - 10.0) + 20.0 {
-                self.rect.w = title_dim.width.max(self.info.close_button_rect.w - 10.0) + 20.0
+
+        let min_w = match self.info.show_titlebar {
+            // Room for the collapse arrow on the left of the title
+            true => {
+                title_dim.width.max(self.info.close_button_rect.w - 10.0)
+                    + self.theme.title_thickness
+                    + 10.0
             }
-        } else {
-            self.rect.w = self.rect.w.max(self.theme.title_thickness);
-            self.rect.h = self.rect.h.max(self.theme.title_thickness);
-        }
+            _ => self.theme.title_thickness,
+        };
 
-        if self.rect.w < self.info.min_size.x {
-            self.rect.w = self.info.min_size.x;
+        let mut min = vec2(min_w, self.collapsed_height()).max(self.info.min_size);
+        if self.info.collapsed {
+            min.y = self.collapsed_height();
         }
+        min
+    }
+
+    fn max_size(&self) -> Vec2 {
+        self.info.max_size.max(self.min_size())
+    }
+
+    pub fn clamp(&mut self) {
+        // CLAMP SIZE
 
-        if self.rect.h < self.info.min_size.y {
-            self.rect.h = self.info.min_size.y;
+        let (min, max) = (self.min_size(), self.max_size());
+        self.rect.w = self.rect.w.clamp(min.x, max.x);
+        if !self.info.collapsed {
+            self.rect.h = self.rect.h.clamp(min.y, max.y);
         }
 
         // CLAMP POSITION
 
         if self.rect.x < 0.0 {
             self.rect.x = 0.0;
-        } else if self.rect.x > screen_width() - self.rect.w {
-            self.rect.x = screen_width() - self.rect.w;
+        } else if self.rect.x > self.screen_size.x - self.rect.w {
+            self.rect.x = self.screen_size.x - self.rect.w;
         }
 
         if self.rect.y < 0.0 {
             self.rect.y = 0.0;
-        } else if self.rect.y > screen_height() - self.rect.h {
-            self.rect.y = screen_height() - self.rect.h;
+        } else if self.rect.y > self.screen_size.y - self.rect.h {
+            self.rect.y = self.screen_size.y - self.rect.h;
         }
     }
 
-    fn handle_close_button(&mut self, window_action: bool) {
+    fn handle_close_button(&mut self, window_action: bool, input: &UiInput) {
         if !self.info.show_titlebar {
             self.info.close_button_hovered = false;
             return;
@@ -614,18 +815,16 @@ impl Window {
 
         if self.info.close_button_rect.contains(self.mouse) && window_action {
             self.info.close_button_hovered = true;
-            if is_mouse_button_pressed(Left) && self.active {
+            if input.is_mouse_button_pressed(Left) && self.active {
                 self.info.close_button_pressed = true;
-            } else if is_mouse_button_released(Left) {
-                if self.info.close_button_pressed {
-                    self.close();
-                }
+            } else if input.is_mouse_button_released(Left) && self.info.close_button_pressed {
+                self.close();
             }
         } else {
             self.info.close_button_hovered = false;
         }
 
-        if is_mouse_button_released(Left) {
+        if input.is_mouse_button_released(Left) {
             self.info.close_button_pressed = false;
         }
 
@@ -642,28 +841,50 @@ impl Window {
         ));
     }
 
-    fn update_resize_handles(&mut self, window_action: bool, taken: bool) {
-        if !self.resize_handles.resizing.is_some()
-            && (self.info.scroll_pressed.is_some() || self.info.scroll_hovered)
-        {
+    fn update_resize_handles(&mut self, window_action: bool, taken: bool, input: &UiInput) {
+        if self.resize_handles.resizing.is_none() && self.on_scrollbar() {
             self.resize_handles.resizing = None;
         } else {
+            let (min, max) = (self.min_size(), self.max_size());
             self.resize_handles
-                .update(&mut self.rect, window_action, taken);
+                .update(&mut self.rect, window_action, taken, input, min, max);
         }
         self.resizing = self.resize_handles.resizing.is_some();
     }
 
-    fn handle_scrolling(&mut self, vertical_offset: f32) {
-        let wheel = mouse_wheel();
-        if wheel.1 != 0.0 {
+    fn on_scrollbar(&self) -> bool {
+        self.info.scroll_hovered
+            || self.info.hscroll_hovered
+            || self.info.scroll_pressed.is_some()
+            || self.info.hscroll_pressed.is_some()
+    }
+
+    fn handle_scrolling(&mut self, vertical_offset: f32, content_width: f32, input: &UiInput) {
+        let wheel = input.mouse_wheel();
+        let shift = input.is_key_down(KeyCode::LeftShift) || input.is_key_down(KeyCode::RightShift);
+        if shift && wheel.1 != 0.0 {
+            self.scroll_x -= wheel.1;
+        } else if wheel.1 != 0.0 {
             self.scroll_y -= wheel.1;
         } else if wheel.0 != 0.0 {
-            self.scroll_y += wheel.0;
+            self.scroll_x += wheel.0;
         }
+
+        // Widgets that stretch to the window (tabs, separators, sliders) don't count as overflow
+        self.max_scroll_x = (content_width - self.rect.w).max(0.0);
+        self.scroll_x = self.scroll_x.clamp(0.0, self.max_scroll_x);
+        self.handle_h_scrollbar(input);
+
+        // Leave room to scroll the last row out from under the bottom scrollbar
+        let h_scrollbar = match self.h_scrollbar_rect() {
+            Some(bar) => bar.h,
+            _ => 0.0,
+        };
+
         self.max_scroll_y = (vertical_offset - self.rect.h
             + self.theme.title_thickness
-            + self.theme.holder_padding)
+            + self.theme.holder_padding
+            + h_scrollbar)
             .max(0.0);
         self.scroll_y = self.scroll_y.clamp(0.0, self.max_scroll_y);
 
@@ -671,7 +892,7 @@ impl Window {
         // SCROLL BAR
         //////////////////////////////////////////
 
-        if is_mouse_button_released(Left) {
+        if input.is_mouse_button_released(Left) {
             self.info.scroll_pressed = None;
         }
 
@@ -706,7 +927,7 @@ impl Window {
 
         if bar_rect.contains(self.mouse) {
             self.info.scroll_hovered = true;
-            if is_mouse_button_pressed(Left) {
+            if input.is_mouse_button_pressed(Left) {
                 self.info.scroll_pressed = Some(self.mouse.y);
             }
         } else {
@@ -723,52 +944,128 @@ impl Window {
         self.scroll_y = self.scroll_y.clamp(0.0, self.max_scroll_y);
     }
 
-    pub fn ensure_render_targets(&mut self, title_thickness: f32) {
-        let win_w = self.rect.w as u32;
-        let win_h = (self.rect.h - title_thickness) as u32;
-        let (screen_w, screen_h) = (screen_width() as u32, screen_height() as u32);
-
-        // Check if screen size changed
-        let needs_update = self
-            .render_targets
-            .target_1
-            .as_ref()
-            .map(|t| t.texture.width() as u32 != win_w || t.texture.height() as u32 != win_h)
-            .unwrap_or(true);
-
-        let update_3 = self
-            .render_targets
-            .target_3
-            .as_ref()
-            .map(|t| t.texture.width() as u32 != screen_w || t.texture.height() as u32 != screen_h)
-            .unwrap_or(true);
-
-        if needs_update {
-            self.render_targets.target_1 = Some(render_target(win_w, win_h));
-            self.render_targets.target_2 = Some(render_target(win_w, win_h));
-
-            self.render_targets
-                .target_1
-                .as_ref()
-                .unwrap()
-                .texture
-                .set_filter(FilterMode::Nearest);
-            self.render_targets
-                .target_2
-                .as_ref()
-                .unwrap()
-                .texture
-                .set_filter(FilterMode::Nearest);
-        }
-
-        if update_3 {
-            self.render_targets.target_3 = Some(render_target(screen_w, screen_h));
-            self.render_targets
-                .target_3
-                .as_ref()
-                .unwrap()
-                .texture
-                .set_filter(FilterMode::Nearest);
+    fn h_scrollbar_rect(&self) -> Option<Rect> {
+        if self.max_scroll_x < 5.0 {
+            return None;
+        }
+
+        let viewport_w = self.rect.w;
+        let content_w = viewport_w + self.max_scroll_x;
+        let thickness = self.theme.scrollbar_thickness;
+
+        let thumb_w = (viewport_w / content_w) * viewport_w;
+        let thumb_x = self.rect.x + (self.scroll_x / self.max_scroll_x) * (viewport_w - thumb_w);
+
+        Some(Rect::new(
+            thumb_x,
+            self.rect.y + self.rect.h - thickness,
+            thumb_w,
+            thickness,
+        ))
+    }
+
+    fn handle_h_scrollbar(&mut self, input: &UiInput) {
+        if input.is_mouse_button_released(Left) {
+            self.info.hscroll_pressed = None;
+        }
+
+        let Some(bar_rect) = self.h_scrollbar_rect() else {
+            self.info.hscroll_hovered = false;
+            return;
+        };
+
+        if bar_rect.contains(self.mouse) {
+            self.info.hscroll_hovered = true;
+            if input.is_mouse_button_pressed(Left) {
+                self.info.hscroll_pressed = Some(self.mouse.x);
+            }
+        } else {
+            self.info.hscroll_hovered = false;
+        }
+
+        // The thumb follows the mouse, so the content moves by the content/viewport ratio
+        if let Some(start_x) = self.info.hscroll_pressed {
+            self.scroll_x +=
+                (self.mouse.x - start_x) * (self.rect.w + self.max_scroll_x) / self.rect.w;
+            self.info.hscroll_pressed = Some(self.mouse.x);
+        }
+
+        self.scroll_x = self.scroll_x.clamp(0.0, self.max_scroll_x);
+    }
+
+    /// Scrolls just enough to show `rect` (in screen space, as computed by the last update).
+    pub(crate) fn scroll_into_view(&mut self, rect: Rect) {
+        let content = self.content_rect();
+        let old_scroll = vec2(self.scroll_x, self.scroll_y);
+
+        if rect.y < content.y {
+            self.scroll_y -= content.y - rect.y;
+        } else if rect.bottom() > content.bottom() {
+            self.scroll_y += rect.bottom() - content.bottom();
+        }
+        self.scroll_y = self.scroll_y.clamp(0.0, self.max_scroll_y);
+
+        if rect.x < content.x {
+            self.scroll_x -= content.x - rect.x;
+        } else if rect.right() > content.right() {
+            self.scroll_x += rect.right() - content.right();
+        }
+        self.scroll_x = self.scroll_x.clamp(0.0, self.max_scroll_x);
+
+        // Keep the stored rects in sync so the focus ring lands in the right place this frame
+        let delta = vec2(self.scroll_x, self.scroll_y) - old_scroll;
+        for (_, r) in self.focus_items.iter_mut() {
+            r.x -= delta.x;
+            r.y -= delta.y;
+        }
+    }
+}
+
+/////////////////////////////////////
+// LAYOUT
+/////////////////////////////////////
+
+impl Window {
+    pub(crate) fn layout(&self, z_order: usize) -> WindowLayout {
+        WindowLayout {
+            pos: Some(self.rect.point()),
+            size: Some(match self.info.collapsed {
+                true => vec2(self.rect.w, self.info.expanded_height),
+                _ => self.rect.size(),
+            }),
+            open: Some(self.open),
+            scroll_y: Some(self.scroll_y),
+            scroll_x: Some(self.scroll_x),
+            collapsed: Some(self.info.collapsed),
+            z_order: Some(z_order),
+        }
+    }
+
+    pub(crate) fn apply_layout(&mut self, layout: &WindowLayout) {
+        if let Some(pos) = layout.pos {
+            self.rect.x = pos.x;
+            self.rect.y = pos.y;
+            self.info.layout_loaded = true;
+        }
+        if let Some(collapsed) = layout.collapsed {
+            self.apply_collapsed(collapsed);
+        }
+        if let Some(size) = layout.size {
+            self.rect.w = size.x;
+            match self.info.collapsed {
+                true => self.info.expanded_height = size.y,
+                _ => self.rect.h = size.y,
+            }
+            self.info.layout_loaded = true;
+        }
+        if let Some(open) = layout.open {
+            self.open = open;
+        }
+        if let Some(scroll_y) = layout.scroll_y {
+            self.scroll_y = scroll_y;
+        }
+        if let Some(scroll_x) = layout.scroll_x {
+            self.scroll_x = scroll_x;
         }
     }
 }
@@ -902,7 +1199,49 @@ impl Window {
 /////////////////////////////////////
 
 impl Window {
+    /// Changes the theme for the widgets created until the matching `pop_style`, like imgui's PushStyleColor/PushStyleVar.
+    pub fn push_style(&mut self, style: StyleVar) -> &mut Window {
+        Rc::make_mut(&mut self.style_stack).push(style);
+        self
+    }
+
+    pub fn pop_style(&mut self) -> &mut Window {
+        Rc::make_mut(&mut self.style_stack).pop();
+        self
+    }
+
+    /// Mixes `id` into the ID of every widget created until the matching `pop_id`,
+    /// so widgets made in a loop don't need their own explicit IDs.
+    pub fn push_id(&mut self, id: impl Hash) -> &mut Window {
+        let mut hasher = DefaultHasher::new();
+        self.id_seed().hash(&mut hasher);
+        id.hash(&mut hasher);
+
+        self.id_stack.push(hasher.finish());
+        self
+    }
+
+    pub fn pop_id(&mut self) -> &mut Window {
+        self.id_stack.pop();
+        self
+    }
+
+    /// Calls `f` between a `push_id(id)` and a `pop_id()`.
+    pub fn with_id(&mut self, id: impl Hash, f: impl FnOnce(&mut Window)) -> &mut Window {
+        self.push_id(id);
+        f(self);
+        self.pop_id()
+    }
+
+    fn id_seed(&self) -> u64 {
+        self.id_stack.last().copied().unwrap_or(0)
+    }
+
     pub fn begin_widgets(&mut self) {
+        // Styles and IDs left pushed at the end of last frame don't carry over
+        self.style_stack = Rc::default();
+        self.id_stack.clear();
+
         // Clear all holder_ids except the main one
         self.holder_ids.clear();
         self.holder_ids.insert(String::from("__Main__"));
@@ -936,13 +1275,19 @@ impl Window {
                 .or_insert_with(|| WidgetHolder::new(false));
         }
         let last_id = self.holder_ids.last().unwrap().clone(); // Clone to satisfy the borrow-checker
-        self.widget_holders.get_mut(&last_id).unwrap()
+        let id_seed = self.id_seed();
+        let holder = self.widget_holders.get_mut(&last_id).unwrap();
+        holder.style = self.style_stack.clone();
+        holder.id_policy = self.id_policy;
+        holder.id_seed = id_seed;
+        holder
     }
 
     // Helper to generate a unique WidgetId string for auto-IDs
     fn generate_widget_id(&self, prefix: &str) -> String {
         let mut hasher = DefaultHasher::new();
         prefix.hash(&mut hasher);
+        self.id_seed().hash(&mut hasher);
         self.holder_ids.len().hash(&mut hasher); // Use current number of holders for uniqueness
         format!("{}_{}", prefix, hasher.finish())
     }
@@ -998,6 +1343,18 @@ impl Window {
             .await
     }
 
+    /// Like `image`, but returns an error instead of panicking if the image can't be loaded.
+    pub async fn try_image(
+        &mut self,
+        id: impl Into<WidgetId>,
+        path: impl ToString,
+        size: Option<Vec2>,
+    ) -> Result<&mut ImageWidget, Error> {
+        self.last_widget_holder()
+            .try_image(id.into(), path.to_string(), size)
+            .await
+    }
+
     pub fn slider(
         &mut self,
         id: impl Into<WidgetId>,
@@ -1091,6 +1448,43 @@ impl Window {
         )
     }
 
+    /// A textbox that only lets in characters `filter` returns `true` for.
+    pub fn input_text_with_filter(
+        &mut self,
+        id: impl Into<WidgetId>,
+        filter: impl Fn(char) -> bool + 'static,
+    ) -> &mut TextBox {
+        self.last_widget_holder()
+            .input_text_with_filter(id.into(), filter)
+    }
+
+    /// A field for whole numbers, with -/+ buttons. Use `.value` to get the number.
+    pub fn input_int(
+        &mut self,
+        id: impl Into<WidgetId>,
+        label: impl ToString,
+        default_value: i64,
+    ) -> &mut InputInt {
+        self.last_widget_holder()
+            .input_number(id.into(), label.to_string(), default_value)
+    }
+
+    /// Like `input_int`, for `f64`s.
+    pub fn input_float(
+        &mut self,
+        id: impl Into<WidgetId>,
+        label: impl ToString,
+        default_value: f64,
+    ) -> &mut InputFloat {
+        self.last_widget_holder()
+            .input_number(id.into(), label.to_string(), default_value)
+    }
+
+    /// A multi-line text box that edits `text` in place. Changes from either side show up the next frame.
+    pub fn text_editor(&mut self, id: impl Into<WidgetId>, text: &mut String) -> &mut TextEditor {
+        self.last_widget_holder().text_editor(id.into(), text)
+    }
+
     pub fn column(&mut self, id: impl Into<WidgetId>, f: impl FnMut(&mut Column) + 'static) -> &mut Column {
         self.last_widget_holder().column(id.into(), f)
     }
@@ -1,8 +1,15 @@
+use crate::ui::draw_list::DrawList;
+use crate::ui::focus::{FocusContext, FocusState, NavInput};
+use crate::ui::fonts::FontStyle;
+use crate::ui::input::UiInput;
 use crate::ui::mouse_action::MouseAction;
+use crate::ui::windows::dock::*;
 use crate::ui::windows::window::Window;
+use crate::ui::windows::window_layout::{parse_layout, write_layout, DockLayout, Layout, WindowLayout};
 use crate::ui::windows::window_theme::WindowTheme;
-use macroquad::miniquad::window::set_mouse_cursor;
+use crate::IdConflictPolicy;
 use macroquad::miniquad::CursorIcon;
+use macroquad::prelude::*;
 use std::collections::HashMap;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
@@ -14,17 +21,69 @@ pub struct WindowHandler {
     latest_active: Vec<WindowId>,
     theme: WindowTheme,
     mouse_action: MouseAction,
+    focus: FocusState,
+    /// Loaded layouts for windows that haven't been created yet.
+    pending_layout: HashMap<String, WindowLayout>,
+    /// Stack positions from the last loaded layout, used to slot new windows into place.
+    layout_z_order: HashMap<WindowId, usize>,
+    docking: Docking,
+    id_policy: IdConflictPolicy,
+    /// Whether a text widget was being edited last frame.
+    text_input: bool,
+    keyboard_nav: bool,
+    /// The mouse cursor for this frame, from the dock or the window under the mouse.
+    cursor: CursorIcon,
 }
 
 impl WindowHandler {
     pub async fn new(font_path: Option<&str>) -> WindowHandler {
+        WindowHandler::with_theme(WindowTheme::new(font_path).await)
+    }
+
+    pub fn with_theme(theme: WindowTheme) -> WindowHandler {
         WindowHandler {
             windows: HashMap::new(),
             safe_queue: vec![],
             latest_active: vec![],
-            theme: WindowTheme::new(font_path).await,
+            theme,
             mouse_action: MouseAction::Normal,
+            focus: FocusState::default(),
+            pending_layout: HashMap::new(),
+            layout_z_order: HashMap::new(),
+            docking: Docking::new(),
+            id_policy: IdConflictPolicy::default(),
+            text_input: false,
+            keyboard_nav: true,
+            cursor: CursorIcon::Default,
+        }
+    }
+
+    pub fn theme(&self) -> &WindowTheme {
+        &self.theme
+    }
+
+    /// Replaces the theme of every window, including ones that already exist.
+    pub fn set_theme(&mut self, theme: WindowTheme) {
+        for win in self.windows.values_mut() {
+            win.set_theme(theme.clone());
         }
+        self.theme = theme;
+    }
+
+    pub fn set_id_conflict_policy(&mut self, policy: IdConflictPolicy) {
+        self.id_policy = policy;
+    }
+
+    pub fn set_keyboard_nav(&mut self, enabled: bool) {
+        self.keyboard_nav = enabled;
+    }
+
+    pub fn wants_text_input(&self) -> bool {
+        self.text_input
+    }
+
+    pub fn cursor(&self) -> CursorIcon {
+        self.cursor
     }
 
     pub fn begin(&mut self, id: impl ToString) -> &mut Window {
@@ -33,28 +92,66 @@ impl WindowHandler {
         self.safe_queue.push(win_id);
 
         if !self.windows.contains_key(&win_id) {
-            self.windows
-                .insert(win_id, Window::new(win_id, name, self.theme.clone()));
-            self.latest_active.insert(0, win_id);
+            let mut win = Window::new(win_id, name.clone(), self.theme.clone());
+
+            match self.pending_layout.remove(&name) {
+                Some(layout) => {
+                    win.apply_layout(&layout);
+                    self.insert_by_z_order(win_id, layout.z_order);
+                }
+                _ => self.latest_active.insert(0, win_id),
+            }
+
+            self.windows.insert(win_id, win);
         }
 
         let w = self.windows.get_mut(&win_id).unwrap();
+        w.id_policy = self.id_policy;
         w.begin_widgets();
         w
     }
 
-    pub fn update(&mut self) -> bool {
+    pub fn update(&mut self, input: &UiInput) -> bool {
         let mut is_active = false;
         let mut active_window = None;
         let mut taken = false;
-        self.mouse_action = MouseAction::Normal;
+
+        self.layout_docks(input.screen_size);
+        self.mouse_action = match self.update_dock_input(input) {
+            true => MouseAction::Dock,
+            _ => MouseAction::Normal,
+        };
+
+        let nav = self.update_focus(input);
+        let mut focus_requested = false;
+        let mut focus_result = None;
+        self.text_input = false;
 
         for i in self.latest_active.clone() {
             let win = self.windows.get_mut(&i).unwrap();
             let id = win.id;
 
-            if win.open {
-                win.update(is_active, self.mouse_action.clone());
+            // Tabs hidden behind another tab in the same dock don't update
+            if win.open && !win.dock_hidden {
+                let mut focus = match (self.focus.window == Some(id), self.focus.visible) {
+                    (true, true) => FocusContext::new(self.focus.widget, nav),
+                    (true, false) => FocusContext::new(self.focus.widget, nav.pointer_focused()),
+                    _ => FocusContext::default(),
+                };
+
+                win.update(is_active, self.mouse_action.clone(), input, &mut focus);
+                self.text_input |= focus.text_input;
+                if let Some(cursor) = win.cursor() {
+                    self.cursor = cursor;
+                }
+
+                if let Some(widget) = focus.requested {
+                    self.focus.window = Some(id);
+                    self.focus.widget = Some(widget);
+                    focus_requested = true;
+                } else if self.focus.window == Some(id) {
+                    focus_result = Some((id, focus.nav_used));
+                }
 
                 if win.hover || win.resizing || win.taken {
                     self.mouse_action = MouseAction::WindowHover(id);
@@ -81,20 +178,181 @@ impl WindowHandler {
             self.latest_active.insert(0, active);
         }
 
+        self.update_dock_drop(input);
+        self.sort_dock_z_order();
+
+        // Clicking anywhere that doesn't take focus drops it
+        if input.is_mouse_button_pressed(MouseButton::Left) && !focus_requested {
+            self.focus.widget = None;
+        }
+
+        if let Some((id, nav_used)) = focus_result {
+            self.apply_nav(id, nav, nav_used);
+        }
+
+        for (id, win) in self.windows.iter_mut() {
+            win.focus_ring = match self.focus.visible && self.focus.window == Some(*id) {
+                true => self.focus.widget,
+                _ => None,
+            };
+        }
+
         taken
     }
 
-    pub fn render(&mut self) {
+    /// Works out which window has focus and returns this frame's navigation input.
+    fn update_focus(&mut self, input: &UiInput) -> NavInput {
+        let mut nav = NavInput::from_input(input, self.keyboard_nav);
+        if let Some(direction) = self.focus.stick_direction(input.left_stick) {
+            nav.move_focus.get_or_insert(direction);
+        }
+
+        // Focus always belongs to the active window
+        let focus_window = self
+            .latest_active
+            .iter()
+            .copied()
+            .find(|id| self.windows.get(id).is_some_and(|w| w.open && w.active));
+
+        if self.focus.window != focus_window {
+            self.focus.window = focus_window;
+            self.focus.widget = None;
+        }
+
+        if input.is_mouse_button_pressed(MouseButton::Left) {
+            self.focus.visible = false;
+        }
+
+        if focus_window.is_some() && nav.moves_focus() {
+            self.focus.visible = true;
+        }
+
+        nav
+    }
+
+    /// Moves focus (Tab / Shift-Tab, D-pad, stick) and closes the window on gamepad B,
+    /// once the focused window's widgets have had their go at `nav`.
+    fn apply_nav(&mut self, id: WindowId, nav: NavInput, nav_used: bool) {
+        let Some(win) = self.windows.get_mut(&id) else {
+            return;
+        };
+
+        let old = self.focus.widget;
+
+        if nav.next || nav.prev {
+            self.focus.step(&win.focus_items, nav.next);
+        } else if let (Some(direction), false) = (nav.move_focus, nav_used) {
+            self.focus.move_spatial(&win.focus_items, direction);
+        }
+
+        if self.focus.widget != old {
+            if let Some((_, rect)) = win
+                .focus_items
+                .iter()
+                .find(|(x, _)| Some(*x) == self.focus.widget)
+            {
+                win.scroll_into_view(*rect);
+            }
+        }
+
+        if nav.back && !nav_used && win.info.closable {
+            win.close();
+            self.focus.widget = None;
+        }
+    }
+
+    /// Saves every window's rect, open/collapsed state, scroll and stack order.
+    pub fn save_layout(&self) -> String {
+        let windows = self
+            .latest_active
+            .iter()
+            .enumerate()
+            .filter_map(|(z, id)| self.windows.get(id).map(|w| (w.name.clone(), w.layout(z))))
+            .collect::<Vec<_>>();
+
+        let name = |id: WindowId| self.windows.get(&id).map(|w| w.name.clone());
+        let docks = self
+            .docking
+            .spaces
+            .iter()
+            .filter(|space| !space.screen || !space.root.windows().is_empty())
+            .map(|space| DockLayout {
+                rect: (!space.screen).then_some(space.rect),
+                root: space.root.to_layout(&name),
+            })
+            .collect();
+
+        write_layout(&Layout { windows, docks })
+    }
+
+    /// Applies a layout from `save_layout`. Windows that don't exist yet pick up their part when they're first created.
+    /// Docks are set up on the next update, and only keep the windows that are shown that frame.
+    pub fn load_layout(&mut self, text: &str) {
+        self.pending_layout.clear();
+        self.layout_z_order.clear();
+
+        let layout = parse_layout(text);
+        self.docking.pending = Some(layout.docks);
+
+        for (name, layout) in layout.windows {
+            let win_id = create_window_id(&name);
+
+            match self.windows.get_mut(&win_id) {
+                Some(win) => {
+                    win.apply_layout(&layout);
+                    if let Some(z) = layout.z_order {
+                        self.layout_z_order.insert(win_id, z);
+                    }
+                }
+                _ => {
+                    self.pending_layout.insert(name, layout);
+                }
+            }
+        }
+
+        // Windows the layout doesn't know about stay at the front
+        let z_order = &self.layout_z_order;
+        self.latest_active
+            .sort_by_key(|id| z_order.get(id).map_or(0, |z| z + 1));
+    }
+
+    fn insert_by_z_order(&mut self, win_id: WindowId, z_order: Option<usize>) {
+        let Some(z) = z_order else {
+            self.latest_active.insert(0, win_id);
+            return;
+        };
+
+        // In front of the first window that was saved further back
+        let index = self
+            .latest_active
+            .iter()
+            .position(|id| self.layout_z_order.get(id).is_some_and(|other| *other > z))
+            .unwrap_or(self.latest_active.len());
+
+        self.latest_active.insert(index, win_id);
+        self.layout_z_order.insert(win_id, z);
+    }
+
+    pub fn render(&mut self, draw_list: &mut DrawList) {
         let mut reversed = self.latest_active.clone();
         reversed.reverse();
 
         for i in reversed {
-            let win = self.windows.get_mut(&i).unwrap();
+            let win = self.windows.get(&i).unwrap();
+
+            if win.open && !win.dock_hidden {
+                win.render(draw_list);
 
-            if win.open {
-                win.render()
+                if let Some((_, leaf)) = self.docking.leaf_of(i) {
+                    self.draw_tab_bar(leaf, draw_list);
+                    if win.info.closable {
+                        win.draw_close_button(draw_list);
+                    }
+                }
             }
         }
+
+        self.draw_dock_overlay(draw_list);
     }
 
     pub fn retain(&mut self) {
@@ -109,14 +367,15 @@ impl WindowHandler {
         }
     }
 
-    pub fn start_windows(&mut self) -> bool {
-        set_mouse_cursor(CursorIcon::Default);
+    pub fn start_windows(&mut self, input: &UiInput) -> bool {
+        self.cursor = CursorIcon::Default;
 
-        self.update() || self.mouse_action != MouseAction::Normal
+        self.update(input) || self.mouse_action != MouseAction::Normal
     }
 
-    pub fn end_windows(&mut self) {
-        self.render();
+    pub fn end_windows(&mut self, draw_list: &mut DrawList) {
+        draw_list.set_fonts(self.theme.fonts.clone());
+        self.render(draw_list);
         self.retain();
     }
 }
@@ -127,3 +386,505 @@ fn create_window_id(id: &str) -> WindowId {
 
     hasher.finish()
 }
+
+/////////////////////////////////////
+// DOCKING
+/////////////////////////////////////
+
+impl WindowHandler {
+    /// Drops closed windows out of their docks, then sizes every docked window to its leaf.
+    fn layout_docks(&mut self, screen_size: Vec2) {
+        if let Some(docks) = self.docking.pending.take() {
+            self.docking.load(&docks, &create_window_id);
+        }
+
+        let windows = &self.windows;
+        let open = |id: WindowId| windows.get(&id).is_some_and(|w| w.open);
+
+        for space in self.docking.spaces.iter_mut() {
+            space.root.retain(&open);
+        }
+
+        // A floating dock space with a single window left turns back into a plain window
+        self.docking
+            .spaces
+            .retain(|space| space.screen || space.root.windows().len() > 1);
+
+        self.docking.spaces[0].rect = Rect::new(0.0, 0.0, screen_size.x, screen_size.y);
+
+        for win in self.windows.values_mut() {
+            win.docked = false;
+            win.dock_hidden = false;
+        }
+
+        self.docking.leaves.clear();
+        self.docking.splits.clear();
+        self.docking.window_docks.clear();
+
+        for (i, space) in self.docking.spaces.iter().enumerate() {
+            let mut leaves = vec![];
+            let mut splits = vec![];
+            space
+                .root
+                .layout(space.rect, &mut vec![], &mut leaves, &mut splits);
+
+            for leaf in leaves {
+                for (tab, id) in leaf.windows.iter().enumerate() {
+                    let Some(win) = self.windows.get_mut(id) else {
+                        continue;
+                    };
+
+                    // Docked windows can't be collapsed, their size comes from the dock
+                    win.apply_collapsed(false);
+                    win.docked = true;
+                    win.dock_hidden = tab != leaf.active;
+                    win.rect = leaf.rect;
+                    self.docking.window_docks.insert(*id, i);
+                }
+
+                self.docking.leaves.push((i, leaf));
+            }
+
+            self.docking
+                .splits
+                .extend(splits.into_iter().map(|split| (i, split)));
+        }
+    }
+
+    /// Splitters, tabs and floating dock spaces. Returns true while the mouse belongs to one of them.
+    fn update_dock_input(&mut self, input: &UiInput) -> bool {
+        let mouse = input.mouse;
+
+        if let Some(drag) = &self.docking.drag {
+            // The dock being dragged can disappear from under the mouse if its windows close
+            let gone = match drag {
+                DockDrag::Splitter { space, path } => self.docking.split_index(*space, path).is_none(),
+                DockDrag::Move { space, .. } | DockDrag::Resize { space } => {
+                    self.docking.space_index(*space).is_none()
+                }
+                DockDrag::Tab { window, .. } => !self.docking.window_docks.contains_key(window),
+            };
+
+            if gone || !input.is_mouse_button_down(MouseButton::Left) {
+                self.docking.drag = None;
+                return false;
+            }
+
+            match *drag {
+                DockDrag::Splitter { space, ref path } => {
+                    let split = self.docking.split_index(space, path).unwrap();
+                    let (dock, split) = &self.docking.splits[split];
+                    let ratio = match split.axis {
+                        SplitAxis::Horizontal => (mouse.x - split.rect.x) / split.rect.w,
+                        SplitAxis::Vertical => (mouse.y - split.rect.y) / split.rect.h,
+                    };
+
+                    if let Some(DockNode::Split { ratio: r, .. }) =
+                        self.docking.spaces[*dock].root.node_mut(&split.path)
+                    {
+                        *r = ratio.clamp(0.1, 0.9);
+                    }
+                    self.layout_docks(input.screen_size);
+                }
+                DockDrag::Tab { window, start } => {
+                    if mouse.distance(start) > TAB_DRAG_DISTANCE {
+                        self.undock(window, mouse);
+                        self.docking.drag = None;
+                        self.layout_docks(input.screen_size);
+                        return false;
+                    }
+                }
+                DockDrag::Move { space, offset } => {
+                    let dock = self.docking.space_index(space).unwrap();
+                    let space = &mut self.docking.spaces[dock];
+                    space.rect.x = (mouse.x + offset.x)
+                        .clamp(0.0, (input.screen_size.x - space.rect.w).max(0.0));
+                    space.rect.y = (mouse.y + offset.y)
+                        .clamp(0.0, (input.screen_size.y - space.rect.h).max(0.0));
+                    self.layout_docks(input.screen_size);
+                }
+                DockDrag::Resize { space } => {
+                    let dock = self.docking.space_index(space).unwrap();
+                    let space = &mut self.docking.spaces[dock];
+                    space.rect.w = (mouse.x - space.rect.x).max(MIN_FLOATING_DOCK_SIZE.x);
+                    space.rect.h = (mouse.y - space.rect.y).max(MIN_FLOATING_DOCK_SIZE.y);
+                    self.layout_docks(input.screen_size);
+                }
+            }
+
+            return true;
+        }
+
+        // Floating windows on top of a dock get the mouse first
+        let top = self.window_at(mouse);
+        let top_dock = top.and_then(|id| self.docking.window_docks.get(&id).copied());
+
+        self.docking.hovered_split = self.docking.splits.iter().position(|(dock, split)| {
+            split.splitter_rect().contains(mouse) && (top.is_none() || top_dock == Some(*dock))
+        });
+
+        if let Some(split) = self.docking.hovered_split {
+            self.cursor = match self.docking.splits[split].1.axis {
+                SplitAxis::Horizontal => CursorIcon::EWResize,
+                SplitAxis::Vertical => CursorIcon::NSResize,
+            };
+
+            if input.is_mouse_button_pressed(MouseButton::Left) {
+                let (dock, split) = &self.docking.splits[split];
+                self.docking.drag = Some(DockDrag::Splitter {
+                    space: self.docking.spaces[*dock].id,
+                    path: split.path.clone(),
+                });
+            }
+            return true;
+        }
+
+        let Some((top, dock)) = top.zip(top_dock) else {
+            return false;
+        };
+        let space = &self.docking.spaces[dock];
+
+        // Bottom-right corner of a floating dock space resizes it
+        let corner = Rect::new(
+            space.rect.right() - DOCK_RESIZE_CORNER,
+            space.rect.bottom() - DOCK_RESIZE_CORNER,
+            DOCK_RESIZE_CORNER,
+            DOCK_RESIZE_CORNER,
+        );
+        if !space.screen && corner.contains(mouse) {
+            self.cursor = CursorIcon::NWSEResize;
+
+            if input.is_mouse_button_pressed(MouseButton::Left) {
+                self.docking.drag = Some(DockDrag::Resize { space: space.id });
+            }
+            return true;
+        }
+
+        if !input.is_mouse_button_pressed(MouseButton::Left) {
+            return false;
+        }
+
+        let Some((_, leaf)) = self.docking.leaf_of(top) else {
+            return false;
+        };
+
+        let tabs = self.tab_rects(leaf);
+        let mut tab_bar = leaf.rect;
+        tab_bar.h = self.theme.title_thickness;
+
+        match tabs.iter().position(|r| r.contains(mouse)) {
+            Some(tab) => {
+                let window = leaf.windows[tab];
+                let path = leaf.path.clone();
+
+                if let Some(DockNode::Leaf { active, .. }) =
+                    self.docking.spaces[dock].root.node_mut(&path)
+                {
+                    *active = tab;
+                }
+                self.layout_docks(input.screen_size);
+
+                // Bring the tab forward so it's the one that takes the click
+                if let Some(idx) = self.latest_active.iter().position(|x| *x == window) {
+                    self.latest_active.remove(idx);
+                    self.latest_active.insert(0, window);
+                }
+
+                self.docking.drag = Some(DockDrag::Tab {
+                    window,
+                    start: mouse,
+                });
+                false
+            }
+            _ if tab_bar.contains(mouse) && !space.screen && !self.close_button_at(top, mouse) => {
+                self.docking.drag = Some(DockDrag::Move {
+                    space: space.id,
+                    offset: space.rect.point() - mouse,
+                });
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Tracks floating windows being dragged over drop targets, and docks them when they're let go.
+    fn update_dock_drop(&mut self, input: &UiInput) {
+        let dragged = self.latest_active.iter().copied().find(|id| {
+            self.windows.get(id).is_some_and(|w| {
+                w.open
+                    && !w.docked
+                    && w.dragging.is_some()
+                    && w.info.dockable
+                    && w.info.show_titlebar
+            })
+        });
+
+        if let Some(id) = dragged {
+            self.docking.dragged_window = Some(id);
+            self.docking.overlay = Some(self.drop_overlay(id, input));
+            return;
+        }
+
+        if let Some(id) = self.docking.dragged_window.take() {
+            let target = self.drop_overlay(id, input).hovered;
+
+            if let (Some((target, _)), true) =
+                (target, input.is_mouse_button_released(MouseButton::Left))
+            {
+                self.dock(id, target);
+                self.layout_docks(input.screen_size);
+            }
+        }
+
+        self.docking.overlay = None;
+    }
+
+    fn drop_overlay(&self, dragged: WindowId, input: &UiInput) -> DropOverlay {
+        let mouse = input.mouse;
+        let mut targets = vec![];
+        let mut hovered = None;
+
+        for (side, rect) in screen_drop_targets(input.screen_size) {
+            if rect.contains(mouse) {
+                let screen = Rect::new(0.0, 0.0, input.screen_size.x, input.screen_size.y);
+                hovered = Some((DockTarget::Screen(side), dock_preview(screen, side, 0.25)));
+            }
+            targets.push((rect, rect.contains(mouse)));
+        }
+
+        // The window under the dragged one
+        let under = self.latest_active.iter().copied().find(|id| {
+            *id != dragged
+                && self.windows.get(id).is_some_and(|w| {
+                    w.open && !w.dock_hidden && w.info.show_titlebar && w.rect.contains(mouse)
+                })
+        });
+
+        if let Some(id) = under {
+            let rect = self.windows[&id].rect;
+
+            for (side, target) in window_drop_targets(rect) {
+                if target.contains(mouse) && hovered.is_none() {
+                    hovered = Some((DockTarget::Window(id, side), dock_preview(rect, side, 0.5)));
+                }
+                targets.push((target, target.contains(mouse)));
+            }
+        }
+
+        DropOverlay { targets, hovered }
+    }
+
+    fn dock(&mut self, id: WindowId, target: DockTarget) {
+        match target {
+            DockTarget::Screen(side) => self.docking.spaces[0].root.split(side, id, 0.25),
+            DockTarget::Window(other, side) => {
+                let (dock, path) = match self.docking.find(other) {
+                    Some(found) => found,
+                    _ => {
+                        let rect = self.windows[&other].rect;
+                        (self.docking.add_floating(rect, other), vec![])
+                    }
+                };
+
+                if let Some(node) = self.docking.spaces[dock].root.node_mut(&path) {
+                    node.split(side, id, 0.5);
+                }
+            }
+        }
+
+        if let Some(win) = self.windows.get_mut(&id) {
+            win.dragging = None;
+        }
+    }
+
+    /// Pulls a window out of its dock and starts dragging it as a floating window.
+    fn undock(&mut self, id: WindowId, mouse: Vec2) {
+        for space in self.docking.spaces.iter_mut() {
+            space.root.remove(id);
+        }
+
+        if let Some(win) = self.windows.get_mut(&id) {
+            win.rect.w = win.rect.w.min(400.0);
+            win.rect.h = win.rect.h.min(300.0);
+            win.rect.x = mouse.x - 30.0;
+            win.rect.y = mouse.y - self.theme.title_thickness / 2.0;
+            win.dragging = Some(win.rect.point() - mouse);
+            win.active = true;
+        }
+    }
+
+    /// Keeps windows of the same floating dock space next to each other in the stack,
+    /// and everything docked to the screen behind the floating windows.
+    fn sort_dock_z_order(&mut self) {
+        let docks = &self.docking.window_docks;
+
+        if let Some(front) = self
+            .latest_active
+            .first()
+            .and_then(|id| docks.get(id).copied())
+            .filter(|dock| *dock != 0)
+        {
+            self.latest_active
+                .sort_by_key(|id| docks.get(id) != Some(&front));
+        }
+
+        self.latest_active
+            .sort_by_key(|id| docks.get(id) == Some(&0));
+    }
+
+    /// The topmost visible window under `position`.
+    fn window_at(&self, position: Vec2) -> Option<WindowId> {
+        self.latest_active.iter().copied().find(|id| {
+            self.windows
+                .get(id)
+                .is_some_and(|w| w.open && !w.dock_hidden && w.rect.contains(position))
+        })
+    }
+
+    fn close_button_at(&self, id: WindowId, position: Vec2) -> bool {
+        let win = &self.windows[&id];
+        win.info.closable && win.info.close_button_rect.contains(position)
+    }
+
+    fn tab_rects(&self, leaf: &DockLeaf) -> Vec<Rect> {
+        let close_w = match self.windows.get(&leaf.windows[leaf.active]) {
+            Some(w) if w.info.closable => self.theme.title_thickness,
+            _ => 0.0,
+        };
+        let available = (leaf.rect.w - close_w - 2.0).max(0.0);
+        let max_w = available / leaf.windows.len() as f32;
+
+        let mut x = leaf.rect.x + 1.0;
+        leaf.windows
+            .iter()
+            .map(|id| {
+                let title = self.windows.get(id).map_or("", |w| w.title.as_str());
+                let title_dim = self.theme.fonts.measure_text(
+                    title,
+                    self.theme.font_for(FontStyle::Bold),
+                    self.theme.title_font_size,
+                    1.0,
+                );
+
+                let w = (title_dim.width + 20.0).min(max_w);
+                let rect = Rect::new(
+                    x,
+                    leaf.rect.y + 1.0,
+                    w - 1.0,
+                    self.theme.title_thickness - 1.0,
+                );
+                x += w;
+                rect
+            })
+            .collect()
+    }
+
+    fn draw_tab_bar(&self, leaf: &DockLeaf, draw_list: &mut DrawList) {
+        draw_list.draw_rounded_rect_ex(
+            leaf.rect.x + 1.0,
+            leaf.rect.y + 1.0,
+            leaf.rect.w - 2.0,
+            self.theme.title_thickness - 1.0,
+            self.theme.border_radius - 1.0,
+            self.theme.border_radius - 1.0,
+            0.0,
+            0.0,
+            self.theme.background,
+        );
+
+        for (i, rect) in self.tab_rects(leaf).into_iter().enumerate() {
+            let Some(win) = self.windows.get(&leaf.windows[i]) else {
+                continue;
+            };
+
+            let color = match (i == leaf.active, win.active) {
+                (true, true) => self.theme.active_titlebar,
+                (true, false) => self.theme.inactive_titlebar,
+                _ => self.theme.inactive_titlebar.with_alpha(0.4),
+            };
+
+            draw_list.draw_rounded_rect_ex(
+                rect.x,
+                rect.y,
+                rect.w,
+                rect.h,
+                self.theme.border_radius - 1.0,
+                self.theme.border_radius - 1.0,
+                0.0,
+                0.0,
+                color,
+            );
+
+            draw_list.set_clip(Some(rect));
+            draw_list.draw_text_ex(
+                &win.title,
+                rect.x + 10.0,
+                rect.y + self.theme.title_thickness - 11.0,
+                TextParams {
+                    font: self.theme.font_for(FontStyle::Bold),
+                    font_size: self.theme.title_font_size,
+                    color: self.theme.title_text,
+                    ..Default::default()
+                },
+            );
+            draw_list.set_clip(None);
+        }
+    }
+
+    fn draw_dock_overlay(&self, draw_list: &mut DrawList) {
+        if let Some(split) = self
+            .docking
+            .hovered_split
+            .or(match &self.docking.drag {
+                Some(DockDrag::Splitter { space, path }) => self.docking.split_index(*space, path),
+                _ => None,
+            })
+            .and_then(|i| self.docking.splits.get(i))
+        {
+            let r = split.1.splitter_rect();
+            draw_list.draw_rectangle(
+                r.x + 2.0,
+                r.y + 2.0,
+                (r.w - 4.0).max(2.0),
+                (r.h - 4.0).max(2.0),
+                self.theme.resize_handle,
+            );
+        }
+
+        let Some(overlay) = &self.docking.overlay else {
+            return;
+        };
+
+        if let Some((_, preview)) = overlay.hovered {
+            draw_list.draw_rectangle(
+                preview.x,
+                preview.y,
+                preview.w,
+                preview.h,
+                self.theme.active_titlebar.with_alpha(0.3),
+            );
+        }
+
+        for (target, hovered) in overlay.targets.iter() {
+            draw_list.draw_rounded_rect(
+                target.x,
+                target.y,
+                target.w,
+                target.h,
+                4.0,
+                match hovered {
+                    true => self.theme.active_titlebar,
+                    _ => self.theme.inactive_titlebar.with_alpha(0.8),
+                },
+            );
+            draw_list.draw_rectangle_lines(
+                target.x + 6.0,
+                target.y + 6.0,
+                target.w - 12.0,
+                target.h - 12.0,
+                2.0,
+                self.theme.dock_target,
+            );
+        }
+    }
+}
@@ -8,13 +8,25 @@ pub struct WindowInfo {
     pub close_color: Color,
     pub scroll_pressed: Option<f32>,
     pub scroll_hovered: bool,
+    pub hscroll_pressed: Option<f32>,
+    pub hscroll_hovered: bool,
     pub ran_once: bool,
     pub min_size: Vec2,
+    pub max_size: Vec2,
     pub show_titlebar: bool,
     pub draggable: bool,
     pub resizable: bool,
     pub closable: bool,
     pub scrollable: bool,
+    pub collapsed: bool,
+    /// The height to go back to when a collapsed window is expanded.
+    pub expanded_height: f32,
+    pub collapse_hovered: bool,
+    /// When the titlebar was last clicked, for double-click detection.
+    pub last_title_click: Option<f64>,
+    pub dockable: bool,
+    /// Set when a saved layout gave this window its rect, so `ActionType::Once` doesn't overwrite it.
+    pub layout_loaded: bool,
 }
 
 impl WindowInfo {
@@ -25,6 +37,8 @@ impl WindowInfo {
             resizable: true,
             closable: true,
             scrollable: true,
+            dockable: true,
+            max_size: Vec2::INFINITY,
 
             ..Default::default()
         }
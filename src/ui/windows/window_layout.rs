@@ -0,0 +1,486 @@
+use crate::ui::windows::dock::SplitAxis;
+use macroquad::prelude::*;
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::vec::IntoIter;
+
+/// The saved state of one window. Anything missing from the file is left as `None` and not touched on load.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WindowLayout {
+    pub pos: Option<Vec2>,
+    pub size: Option<Vec2>,
+    pub open: Option<bool>,
+    pub scroll_y: Option<f32>,
+    pub scroll_x: Option<f32>,
+    pub collapsed: Option<bool>,
+    /// Position in the window stack, 0 being the front.
+    pub z_order: Option<usize>,
+}
+
+/// The saved tree of one dock space, with windows by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DockLayout {
+    /// Where a floating dock space sits. `None` for the screen dock space.
+    pub rect: Option<Rect>,
+    pub root: DockNodeLayout,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DockNodeLayout {
+    Leaf {
+        windows: Vec<String>,
+        active: usize,
+        central: bool,
+    },
+    Split {
+        axis: SplitAxis,
+        ratio: f32,
+        first: Box<DockNodeLayout>,
+        second: Box<DockNodeLayout>,
+    },
+}
+
+/// Everything `save_layout` writes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Layout {
+    pub windows: Vec<(String, WindowLayout)>,
+    pub docks: Vec<DockLayout>,
+}
+
+/// One line of a `[Dock]` section. The nodes are listed depth first, each split followed by its two children.
+enum DockEntry {
+    Split(SplitAxis, f32),
+    Leaf { active: usize, central: bool },
+    Tab(String),
+}
+
+enum Section {
+    None,
+    Window,
+    Dock {
+        screen: bool,
+        rect: Option<Rect>,
+        /// `None` for node lines that didn't parse.
+        entries: Vec<Option<DockEntry>>,
+    },
+}
+
+/// Parses an ini-like layout, the same shape as dear imgui's imgui.ini:
+///
+/// ```ini
+/// [Window][Settings]
+/// Pos=10,20
+/// Size=300,200
+/// Open=1
+/// Scroll=0
+/// ScrollX=0
+/// Collapsed=0
+/// ZOrder=0
+///
+/// [Dock][Screen]
+/// Split=H,0.25
+/// Leaf=0
+/// Tab=Settings
+/// Central=0
+/// ```
+///
+/// Unknown sections, unknown keys and values that don't parse (including `NaN` and `inf`) are skipped.
+/// So are `[Dock]` sections whose nodes don't make up a whole tree.
+pub fn parse_layout(text: &str) -> Layout {
+    let mut layout = Layout::default();
+    let mut section = Section::None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') || line.starts_with('#') {
+            continue;
+        }
+
+        if line.starts_with('[') {
+            finish_dock(std::mem::replace(&mut section, Section::None), &mut layout.docks);
+
+            if let Some(name) = line
+                .strip_prefix("[Window][")
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                layout.windows.push((name.to_string(), WindowLayout::default()));
+                section = Section::Window;
+            } else if line == "[Dock][Screen]" || line == "[Dock][Floating]" {
+                section = Section::Dock {
+                    screen: line == "[Dock][Screen]",
+                    rect: None,
+                    entries: vec![],
+                };
+            }
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+
+        match &mut section {
+            Section::Window => {
+                let Some((_, window)) = layout.windows.last_mut() else {
+                    continue;
+                };
+                parse_window_key(window, key.trim(), value);
+            }
+            Section::Dock { rect, entries, .. } => {
+                let entry = match key.trim() {
+                    "Rect" => {
+                        *rect = parse_rect(value).or(*rect);
+                        continue;
+                    }
+                    "Split" => parse_split(value),
+                    "Leaf" => value.parse().ok().map(|active| DockEntry::Leaf {
+                        active,
+                        central: false,
+                    }),
+                    "Central" => value.parse().ok().map(|active| DockEntry::Leaf {
+                        active,
+                        central: true,
+                    }),
+                    "Tab" => Some(DockEntry::Tab(value.to_string())),
+                    _ => continue,
+                };
+
+                entries.push(entry);
+            }
+            Section::None => {}
+        }
+    }
+
+    finish_dock(section, &mut layout.docks);
+    layout
+}
+
+fn parse_window_key(layout: &mut WindowLayout, key: &str, value: &str) {
+    match key {
+        "Pos" => layout.pos = parse_vec2(value).or(layout.pos),
+        "Size" => layout.size = parse_vec2(value).or(layout.size),
+        "Open" => layout.open = parse_bool(value).or(layout.open),
+        "Scroll" => layout.scroll_y = parse_f32(value).or(layout.scroll_y),
+        "ScrollX" => layout.scroll_x = parse_f32(value).or(layout.scroll_x),
+        "Collapsed" => layout.collapsed = parse_bool(value).or(layout.collapsed),
+        "ZOrder" => layout.z_order = value.parse().ok().or(layout.z_order),
+        _ => {}
+    }
+}
+
+/// Turns a finished `[Dock]` section into a tree, if it makes one.
+fn finish_dock(section: Section, docks: &mut Vec<DockLayout>) {
+    let Section::Dock {
+        screen,
+        rect,
+        entries,
+    } = section
+    else {
+        return;
+    };
+
+    // Floating dock spaces need somewhere to go
+    if !screen && rect.is_none() {
+        return;
+    }
+
+    let mut entries = entries.into_iter().peekable();
+    let root = build_dock_node(&mut entries);
+
+    if let (Some(root), None) = (root, entries.next()) {
+        docks.push(DockLayout {
+            rect: rect.filter(|_| !screen),
+            root,
+        });
+    }
+}
+
+/// A bad node line would shift every node after it, so it fails the whole tree.
+fn build_dock_node(
+    entries: &mut Peekable<IntoIter<Option<DockEntry>>>,
+) -> Option<DockNodeLayout> {
+    match entries.next()?? {
+        DockEntry::Split(axis, ratio) => Some(DockNodeLayout::Split {
+            axis,
+            ratio,
+            first: Box::new(build_dock_node(entries)?),
+            second: Box::new(build_dock_node(entries)?),
+        }),
+        DockEntry::Leaf { active, central } => {
+            let mut windows = vec![];
+            while let Some(Some(DockEntry::Tab(_))) = entries.peek() {
+                if let Some(Some(DockEntry::Tab(name))) = entries.next() {
+                    windows.push(name);
+                }
+            }
+
+            Some(DockNodeLayout::Leaf {
+                windows,
+                active,
+                central,
+            })
+        }
+        DockEntry::Tab(_) => None,
+    }
+}
+
+pub fn write_layout(layout: &Layout) -> String {
+    let mut text = String::new();
+
+    for (name, layout) in layout.windows.iter() {
+        let _ = writeln!(text, "[Window][{name}]");
+
+        if let Some(pos) = layout.pos {
+            let _ = writeln!(text, "Pos={},{}", pos.x, pos.y);
+        }
+        if let Some(size) = layout.size {
+            let _ = writeln!(text, "Size={},{}", size.x, size.y);
+        }
+        if let Some(open) = layout.open {
+            let _ = writeln!(text, "Open={}", open as u8);
+        }
+        if let Some(scroll_y) = layout.scroll_y {
+            let _ = writeln!(text, "Scroll={scroll_y}");
+        }
+        if let Some(scroll_x) = layout.scroll_x {
+            let _ = writeln!(text, "ScrollX={scroll_x}");
+        }
+        if let Some(collapsed) = layout.collapsed {
+            let _ = writeln!(text, "Collapsed={}", collapsed as u8);
+        }
+        if let Some(z_order) = layout.z_order {
+            let _ = writeln!(text, "ZOrder={z_order}");
+        }
+
+        text.push('\n');
+    }
+
+    for dock in layout.docks.iter() {
+        match dock.rect {
+            Some(rect) => {
+                let _ = writeln!(text, "[Dock][Floating]");
+                let _ = writeln!(text, "Rect={},{},{},{}", rect.x, rect.y, rect.w, rect.h);
+            }
+            _ => {
+                let _ = writeln!(text, "[Dock][Screen]");
+            }
+        }
+
+        write_dock_node(&mut text, &dock.root);
+        text.push('\n');
+    }
+
+    text
+}
+
+fn write_dock_node(text: &mut String, node: &DockNodeLayout) {
+    match node {
+        DockNodeLayout::Leaf {
+            windows,
+            active,
+            central,
+        } => {
+            let key = match central {
+                true => "Central",
+                _ => "Leaf",
+            };
+            let _ = writeln!(text, "{key}={active}");
+
+            for name in windows {
+                let _ = writeln!(text, "Tab={name}");
+            }
+        }
+        DockNodeLayout::Split {
+            axis,
+            ratio,
+            first,
+            second,
+        } => {
+            let axis = match axis {
+                SplitAxis::Horizontal => "H",
+                SplitAxis::Vertical => "V",
+            };
+            let _ = writeln!(text, "Split={axis},{ratio}");
+
+            write_dock_node(text, first);
+            write_dock_node(text, second);
+        }
+    }
+}
+
+fn parse_vec2(value: &str) -> Option<Vec2> {
+    let (x, y) = value.split_once(',')?;
+    Some(vec2(parse_f32(x.trim())?, parse_f32(y.trim())?))
+}
+
+fn parse_rect(value: &str) -> Option<Rect> {
+    let mut parts = value.split(',').map(|v| parse_f32(v.trim()));
+    let rect = Rect::new(parts.next()??, parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(rect)
+}
+
+fn parse_split(value: &str) -> Option<DockEntry> {
+    let (axis, ratio) = value.split_once(',')?;
+    let axis = match axis.trim() {
+        "H" => SplitAxis::Horizontal,
+        "V" => SplitAxis::Vertical,
+        _ => return None,
+    };
+
+    Some(DockEntry::Split(axis, parse_f32(ratio.trim())?))
+}
+
+fn parse_f32(value: &str) -> Option<f32> {
+    value.parse().ok().filter(|v: &f32| v.is_finite())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "true" => Some(true),
+        "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(windows: &[&str], active: usize) -> DockNodeLayout {
+        DockNodeLayout::Leaf {
+            windows: windows.iter().map(|w| w.to_string()).collect(),
+            active,
+            central: false,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let layout = Layout {
+            windows: vec![
+                (
+                    String::from("Settings"),
+                    WindowLayout {
+                        pos: Some(vec2(10.0, 20.5)),
+                        size: Some(vec2(300.0, 200.0)),
+                        open: Some(false),
+                        scroll_y: Some(12.0),
+                        scroll_x: Some(0.0),
+                        collapsed: Some(true),
+                        z_order: Some(1),
+                    },
+                ),
+                (String::from("Empty"), WindowLayout::default()),
+            ],
+            docks: vec![
+                DockLayout {
+                    rect: None,
+                    root: DockNodeLayout::Split {
+                        axis: SplitAxis::Horizontal,
+                        ratio: 0.25,
+                        first: Box::new(leaf(&["Settings", "Log"], 1)),
+                        second: Box::new(DockNodeLayout::Leaf {
+                            windows: vec![],
+                            active: 0,
+                            central: true,
+                        }),
+                    },
+                },
+                DockLayout {
+                    rect: Some(Rect::new(40.0, 50.0, 300.0, 200.0)),
+                    root: DockNodeLayout::Split {
+                        axis: SplitAxis::Vertical,
+                        ratio: 0.5,
+                        first: Box::new(leaf(&["A"], 0)),
+                        second: Box::new(leaf(&["B"], 0)),
+                    },
+                },
+            ],
+        };
+
+        assert_eq!(parse_layout(&write_layout(&layout)), layout);
+    }
+
+    #[test]
+    fn unknown_sections_and_keys_are_skipped() {
+        let text = "\
+            [Docking][Data]\n\
+            Pos=1,2\n\
+            [Window][Log]\n\
+            ; a comment\n\
+            Pos=5,6\n\
+            Color=red\n\
+            no equals sign\n\
+            [Dock][Screen]\n\
+            Color=red\n\
+            Leaf=0\n\
+            Tab=Log\n";
+
+        let layout = parse_layout(text);
+        assert_eq!(
+            layout.windows,
+            vec![(
+                String::from("Log"),
+                WindowLayout {
+                    pos: Some(vec2(5.0, 6.0)),
+                    ..Default::default()
+                }
+            )]
+        );
+        assert_eq!(
+            layout.docks,
+            vec![DockLayout {
+                rect: None,
+                root: leaf(&["Log"], 0),
+            }]
+        );
+    }
+
+    #[test]
+    fn bad_values_are_skipped() {
+        let text = "\
+            [Window][Log]\n\
+            Pos=NaN,inf\n\
+            Size=100\n\
+            Open=maybe\n\
+            Scroll=-inf\n\
+            ScrollX=abc\n\
+            ZOrder=-1\n";
+
+        assert_eq!(parse_layout(text).windows[0].1, WindowLayout::default());
+    }
+
+    #[test]
+    fn a_bad_value_keeps_the_earlier_one() {
+        let text = "[Window][Log]\nPos=1,2\nPos=x,y\n";
+
+        assert_eq!(parse_layout(text).windows[0].1.pos, Some(vec2(1.0, 2.0)));
+    }
+
+    #[test]
+    fn broken_dock_trees_are_skipped() {
+        let text = "\
+            [Dock][Screen]\n\
+            Split=H,0.5\n\
+            Leaf=0\n\
+            Tab=A\n\
+            [Dock][Floating]\n\
+            Rect=0,0,100,100\n\
+            Split=X,0.5\n\
+            Leaf=0\n\
+            Leaf=0\n\
+            [Dock][Floating]\n\
+            Rect=0,0,NaN,100\n\
+            Leaf=0\n\
+            [Dock][Floating]\n\
+            Leaf=0\n\
+            Tab=A\n\
+            [Window][A]\n\
+            Open=1\n";
+
+        let layout = parse_layout(text);
+        assert!(layout.docks.is_empty());
+        assert_eq!(layout.windows.len(), 1);
+    }
+}
@@ -1,17 +1,35 @@
+use crate::Error;
+use crate::ui::fonts::{FontSet, FontStyle, UiFont};
 use macroquad::prelude::*;
+use std::fmt::{Display, Write};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
 
 #[derive(Clone)]
 pub struct WindowTheme {
     pub font: Option<Font>,
+    /// Bold, italic and monospace fonts, and fallbacks for missing glyphs. Set with `set_font`.
+    pub fonts: FontSet,
     pub title_thickness: f32,
     pub scrollbar_thickness: f32,
     pub holder_padding: f32,
     pub border_radius: f32,
+    pub font_size: u16,
+    pub title_font_size: u16,
+    /// Space after each widget.
+    pub widget_padding: f32,
+    pub button_rounding: f32,
+    /// Corner radius of checkboxes, sliders, progress bars and dropdowns.
+    pub frame_rounding: f32,
 
+    // WINDOW
     pub background: Color,
     pub inactive_titlebar: Color,
     pub active_titlebar: Color,
+    pub title_text: Color,
+    pub title_button_hover: Color,
     pub win_stroke: Color,
     pub active_stroke: Color,
     pub hover_stroke: Color,
@@ -19,26 +37,139 @@ pub struct WindowTheme {
     pub close_button_hover: Color,
     pub close_button_press: Color,
     pub resize_handle: Color,
+    pub focus_ring: Color,
+    pub scrollbar: Color,
+    pub scrollbar_hover: Color,
+    pub scrollbar_press: Color,
+    pub dock_target: Color,
+
+    // WIDGETS
+    pub text: Color,
+    pub text_muted: Color,
+
+    pub button: Color,
+    pub button_text: Color,
+    pub button_stroke: Color,
+
+    pub checkbox: Color,
+    pub checkbox_hover: Color,
+    pub checkbox_press: Color,
+    pub checkbox_check: Color,
+    pub checkbox_mark: Color,
+
+    pub radio: Color,
+    pub radio_hover: Color,
+    pub radio_press: Color,
+    pub radio_dot: Color,
+
+    pub slider: Color,
+    pub slider_hover: Color,
+    pub slider_press: Color,
+    pub slider_grab: Color,
+
+    pub progress_bar: Color,
+    pub progress_fill: Color,
+
+    pub dropdown: Color,
+    pub dropdown_hover: Color,
+    pub dropdown_press: Color,
+    pub dropdown_button: Color,
+    pub dropdown_arrow: Color,
+    pub dropdown_list: Color,
+    pub dropdown_list_stroke: Color,
+    pub dropdown_item_hover: Color,
+    pub dropdown_item_press: Color,
+
+    pub textbox: Color,
+    pub textbox_hover: Color,
+    pub textbox_stroke: Color,
+    pub textbox_hover_stroke: Color,
+    pub textbox_edit_stroke: Color,
+    pub caret: Color,
+    pub selection: Color,
+
+    pub separator: Color,
+
+    pub tab_bar: Color,
+    pub tab_active: Color,
+    pub tab_active_stroke: Color,
+    pub tab_underline: Color,
+    pub tab_hover: Color,
+    pub tab_hover_stroke: Color,
+    pub tab_press: Color,
+    pub tab_press_stroke: Color,
+}
+
+impl Default for WindowTheme {
+    fn default() -> Self {
+        WindowTheme::dark()
+    }
 }
 
+/// The built-in palettes, for switching at runtime with `UI::set_theme_preset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThemePreset {
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemePreset {
+    pub const ALL: [ThemePreset; 3] = [ThemePreset::Dark, ThemePreset::Light, ThemePreset::HighContrast];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ThemePreset::Dark => "dark",
+            ThemePreset::Light => "light",
+            ThemePreset::HighContrast => "high_contrast",
+        }
+    }
+
+    pub fn theme(self) -> WindowTheme {
+        match self {
+            ThemePreset::Dark => WindowTheme::dark(),
+            ThemePreset::Light => WindowTheme::light(),
+            ThemePreset::HighContrast => WindowTheme::high_contrast(),
+        }
+    }
+}
+
+impl FromStr for ThemePreset {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ThemePreset::ALL
+            .into_iter()
+            .find(|p| p.name() == name)
+            .ok_or_else(|| format!("unknown theme preset `{name}`"))
+    }
+}
+
+/////////////////////////////////////
+// PRESETS
+/////////////////////////////////////
+
 impl WindowTheme {
-    pub async fn new(font_path: Option<&str>) -> WindowTheme {
+    /// The default dark blue theme.
+    pub fn dark() -> WindowTheme {
         WindowTheme {
-            font: match font_path {
-                Some(font_path) => match fs::exists(font_path).unwrap() {
-                    true => Some(load_ttf_font(font_path).await.unwrap()),
-                    _ => None,
-                },
-                _ => None,
-            },
+            font: None,
+            fonts: FontSet::default(),
             title_thickness: 30.0,
             scrollbar_thickness: 10.0,
             holder_padding: 5.0,
             border_radius: 7.0,
+            font_size: 14,
+            title_font_size: 13,
+            widget_padding: 5.0,
+            button_rounding: 3.0,
+            frame_rounding: 0.0,
 
             background: Color::new(0.1, 0.1, 0.1, 1.0),
             active_titlebar: Color::new(0.2, 0.4, 0.7, 1.0),
             inactive_titlebar: Color::new(0.1, 0.3, 0.5, 1.0),
+            title_text: WHITE,
+            title_button_hover: WHITE.with_alpha(0.1),
             win_stroke: Color::new(0.3,0.3,0.3,1.0),
             active_stroke: Color::new(0.55,0.55,0.55,1.0),
             hover_stroke: Color::new(0.4,0.4,0.4,1.0),
@@ -46,6 +177,753 @@ impl WindowTheme {
             close_button_hover: RED,
             close_button_press: Color::new(0.7, 0.1, 0.1, 1.0),
             resize_handle: Color::new(0.3, 0.5, 0.7, 1.0),
+            focus_ring: Color::new(0.45, 0.7, 1.0, 1.0),
+            scrollbar: WHITE.with_alpha(0.2),
+            scrollbar_hover: WHITE.with_alpha(0.4),
+            scrollbar_press: WHITE.with_alpha(0.6),
+            dock_target: WHITE.with_alpha(0.7),
+
+            text: WHITE,
+            text_muted: Color::new(0.9, 0.9, 0.9, 0.9),
+
+            button: Color::new(0.09, 0.25, 0.45, 1.0),
+            button_text: WHITE,
+            button_stroke: WHITE,
+
+            checkbox: Color::new(0.05, 0.2, 0.4, 1.0),
+            checkbox_hover: Color::new(0.2, 0.4, 0.6, 1.0),
+            checkbox_press: Color::new(0.3, 0.5, 0.75, 1.0),
+            checkbox_check: Color::new(0.3, 0.7, 1.0, 1.0),
+            checkbox_mark: WHITE,
+
+            radio: Color::new(0.2, 0.3, 0.6, 0.8),
+            radio_hover: Color::new(0.22, 0.35, 0.55, 0.9),
+            radio_press: Color::new(0.2, 0.3, 0.5, 0.7),
+            radio_dot: Color::new(0.3, 0.6, 0.9, 1.0),
+
+            slider: Color::new(0.1, 0.25, 0.4, 1.0),
+            slider_hover: Color::new(0.15, 0.3, 0.5, 1.0),
+            slider_press: Color::new(0.2, 0.35, 0.55, 1.0),
+            slider_grab: Color::new(0.34, 0.54, 0.8, 1.0),
+
+            progress_bar: Color::new(0.1, 0.25, 0.4, 1.0),
+            progress_fill: Color::new(0.34, 0.54, 0.8, 1.0),
+
+            dropdown: Color::new(0.1, 0.3, 0.5, 0.9),
+            dropdown_hover: Color::new(0.2, 0.4, 0.7, 0.9),
+            dropdown_press: Color::new(0.3, 0.5, 0.8, 1.0),
+            dropdown_button: Color::new(0.4, 0.7, 1.0, 1.0),
+            dropdown_arrow: WHITE,
+            dropdown_list: BLACK,
+            dropdown_list_stroke: DARKGRAY,
+            dropdown_item_hover: Color::new(0.15, 0.15, 0.15, 1.0),
+            dropdown_item_press: Color::new(0.2, 0.4, 0.6, 1.0),
+
+            textbox: WHITE.with_alpha(0.2),
+            textbox_hover: WHITE.with_alpha(0.4),
+            textbox_stroke: WHITE.with_alpha(0.5),
+            textbox_hover_stroke: Color::new(0.1, 0.47, 0.95, 0.8),
+            textbox_edit_stroke: Color::new(0.2, 0.6, 1.0, 1.0),
+            caret: WHITE,
+            selection: DARKBLUE.with_alpha(0.88),
+
+            separator: Color::new(1.0, 1.0, 1.0, 0.65),
+
+            tab_bar: BLACK,
+            tab_active: Color::new(0.2, 0.4, 0.7, 0.2),
+            tab_active_stroke: Color::new(0.2, 0.5, 0.7, 0.3),
+            tab_underline: Color::new(0.2, 0.5, 0.8, 1.0),
+            tab_hover: WHITE.with_alpha(0.1),
+            tab_hover_stroke: WHITE.with_alpha(0.3),
+            tab_press: Color::new(0.2, 0.5, 0.9, 0.2),
+            tab_press_stroke: WHITE.with_alpha(0.5),
+        }
+    }
+
+    /// Dark text on light grey windows, for bright rooms.
+    pub fn light() -> WindowTheme {
+        let text = Color::new(0.1, 0.1, 0.12, 1.0);
+        let accent = Color::new(0.2, 0.47, 0.87, 1.0);
+
+        WindowTheme {
+            background: Color::new(0.94, 0.94, 0.95, 1.0),
+            active_titlebar: Color::new(0.6, 0.75, 0.95, 1.0),
+            inactive_titlebar: Color::new(0.82, 0.85, 0.9, 1.0),
+            title_text: text,
+            title_button_hover: BLACK.with_alpha(0.1),
+            win_stroke: Color::new(0.7, 0.7, 0.72, 1.0),
+            active_stroke: Color::new(0.4, 0.4, 0.45, 1.0),
+            hover_stroke: Color::new(0.55, 0.55, 0.6, 1.0),
+            close_button: Color::new(0.82, 0.85, 0.9, 1.0),
+            close_button_hover: Color::new(0.9, 0.25, 0.25, 1.0),
+            close_button_press: Color::new(0.7, 0.1, 0.1, 1.0),
+            resize_handle: Color::new(0.35, 0.55, 0.85, 1.0),
+            focus_ring: accent,
+            scrollbar: BLACK.with_alpha(0.2),
+            scrollbar_hover: BLACK.with_alpha(0.35),
+            scrollbar_press: BLACK.with_alpha(0.5),
+            dock_target: accent.with_alpha(0.7),
+
+            text,
+            text_muted: Color::new(0.3, 0.3, 0.35, 0.9),
+
+            button: Color::new(0.7, 0.8, 0.95, 1.0),
+            button_text: text,
+            button_stroke: accent,
+
+            checkbox: Color::new(0.82, 0.85, 0.9, 1.0),
+            checkbox_hover: Color::new(0.74, 0.8, 0.9, 1.0),
+            checkbox_press: Color::new(0.62, 0.72, 0.88, 1.0),
+            checkbox_check: accent,
+            checkbox_mark: WHITE,
+
+            radio: Color::new(0.78, 0.83, 0.9, 1.0),
+            radio_hover: Color::new(0.7, 0.78, 0.9, 1.0),
+            radio_press: Color::new(0.62, 0.72, 0.88, 1.0),
+            radio_dot: accent,
+
+            slider: Color::new(0.82, 0.85, 0.9, 1.0),
+            slider_hover: Color::new(0.76, 0.81, 0.89, 1.0),
+            slider_press: Color::new(0.7, 0.77, 0.88, 1.0),
+            slider_grab: accent,
+
+            progress_bar: Color::new(0.82, 0.85, 0.9, 1.0),
+            progress_fill: accent,
+
+            dropdown: Color::new(0.82, 0.85, 0.9, 1.0),
+            dropdown_hover: Color::new(0.74, 0.8, 0.92, 1.0),
+            dropdown_press: Color::new(0.64, 0.74, 0.92, 1.0),
+            dropdown_button: accent,
+            dropdown_arrow: WHITE,
+            dropdown_list: Color::new(0.98, 0.98, 0.99, 1.0),
+            dropdown_list_stroke: Color::new(0.65, 0.65, 0.7, 1.0),
+            dropdown_item_hover: Color::new(0.88, 0.9, 0.94, 1.0),
+            dropdown_item_press: Color::new(0.7, 0.8, 0.95, 1.0),
+
+            textbox: BLACK.with_alpha(0.06),
+            textbox_hover: BLACK.with_alpha(0.12),
+            textbox_stroke: BLACK.with_alpha(0.35),
+            textbox_hover_stroke: accent.with_alpha(0.8),
+            textbox_edit_stroke: accent,
+            caret: text,
+            selection: Color::new(0.55, 0.75, 1.0, 0.6),
+
+            separator: BLACK.with_alpha(0.35),
+
+            tab_bar: Color::new(0.85, 0.87, 0.9, 1.0),
+            tab_active: accent.with_alpha(0.2),
+            tab_active_stroke: accent.with_alpha(0.4),
+            tab_underline: accent,
+            tab_hover: BLACK.with_alpha(0.06),
+            tab_hover_stroke: BLACK.with_alpha(0.2),
+            tab_press: accent.with_alpha(0.25),
+            tab_press_stroke: BLACK.with_alpha(0.35),
+
+            ..WindowTheme::dark()
+        }
+    }
+
+    /// Pure black and white with yellow and cyan highlights, and an outline around everything you can click.
+    pub fn high_contrast() -> WindowTheme {
+        let accent = Color::new(1.0, 0.85, 0.0, 1.0);
+        let hover = Color::new(0.0, 1.0, 1.0, 1.0);
+        let raised = Color::new(0.2, 0.2, 0.2, 1.0);
+        let pressed = Color::new(0.35, 0.35, 0.35, 1.0);
+
+        WindowTheme {
+            background: BLACK,
+            active_titlebar: accent,
+            inactive_titlebar: Color::new(0.75, 0.75, 0.75, 1.0),
+            title_text: BLACK,
+            title_button_hover: BLACK.with_alpha(0.25),
+            win_stroke: WHITE,
+            active_stroke: accent,
+            hover_stroke: hover,
+            close_button: Color::new(0.75, 0.75, 0.75, 1.0),
+            close_button_hover: RED,
+            close_button_press: Color::new(0.7, 0.0, 0.0, 1.0),
+            resize_handle: hover,
+            focus_ring: hover,
+            scrollbar: WHITE.with_alpha(0.6),
+            scrollbar_hover: WHITE.with_alpha(0.8),
+            scrollbar_press: WHITE,
+            dock_target: accent.with_alpha(0.8),
+
+            text: WHITE,
+            text_muted: Color::new(0.85, 0.85, 0.85, 1.0),
+
+            button: Color::new(0.1, 0.1, 0.1, 1.0),
+            button_text: WHITE,
+            button_stroke: accent,
+
+            checkbox: raised,
+            checkbox_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            checkbox_press: pressed,
+            checkbox_check: accent,
+            checkbox_mark: BLACK,
+
+            radio: raised,
+            radio_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            radio_press: pressed,
+            radio_dot: accent,
+
+            slider: raised,
+            slider_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            slider_press: pressed,
+            slider_grab: accent,
+
+            progress_bar: raised,
+            progress_fill: accent,
+
+            dropdown: raised,
+            dropdown_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            dropdown_press: pressed,
+            dropdown_button: accent,
+            dropdown_arrow: BLACK,
+            dropdown_list: BLACK,
+            dropdown_list_stroke: WHITE,
+            dropdown_item_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            dropdown_item_press: pressed,
+
+            textbox: raised,
+            textbox_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            textbox_stroke: WHITE,
+            textbox_hover_stroke: hover,
+            textbox_edit_stroke: accent,
+            caret: accent,
+            selection: Color::new(0.0, 0.4, 0.8, 1.0),
+
+            separator: WHITE,
+
+            tab_bar: BLACK,
+            tab_active: raised,
+            tab_active_stroke: WHITE,
+            tab_underline: accent,
+            tab_hover: Color::new(0.3, 0.3, 0.3, 1.0),
+            tab_hover_stroke: hover,
+            tab_press: pressed,
+            tab_press_stroke: accent,
+
+            ..WindowTheme::dark()
+        }
+    }
+}
+
+impl WindowTheme {
+    /// The default theme with the font at `font_path`. If there's no path, or the font can't be loaded,
+    /// the embedded font is used instead (see `UiFont::embedded`). Use `try_new` to find out why it failed.
+    pub async fn new(font_path: Option<&str>) -> WindowTheme {
+        match WindowTheme::try_new(font_path).await {
+            Ok(theme) => theme,
+            _ => WindowTheme::with_font(UiFont::embedded()),
+        }
+    }
+
+    /// Like `new`, but returns an error if the font at `font_path` can't be loaded.
+    pub async fn try_new(font_path: Option<&str>) -> Result<WindowTheme, Error> {
+        let font = match font_path {
+            Some(path) => Some(UiFont::load_async(path).await?),
+            _ => UiFont::embedded(),
+        };
+
+        Ok(WindowTheme::with_font(font))
+    }
+
+    pub fn from_font_bytes(bytes: &[u8]) -> Result<WindowTheme, Error> {
+        Ok(WindowTheme::with_font(Some(UiFont::from_bytes(bytes)?)))
+    }
+
+    fn with_font(font: Option<UiFont>) -> WindowTheme {
+        let mut theme = WindowTheme::default();
+        theme.set_font(FontStyle::Regular, font);
+        theme
+    }
+
+    /// Puts `font` in one of the style slots. The regular font is also what `font` draws with.
+    pub fn set_font(&mut self, style: FontStyle, font: Option<UiFont>) -> &mut Self {
+        if style == FontStyle::Regular {
+            self.font = font.as_ref().map(|f| f.font.clone());
+        }
+
+        self.fonts.set(style, font);
+        self
+    }
+
+    /// The font for `style`, or the regular font if that slot is empty.
+    pub fn font_for(&self, style: FontStyle) -> Option<&Font> {
+        match self.fonts.get(style) {
+            Some(f) => Some(&f.font),
+            _ => self.font.as_ref(),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<WindowTheme, ThemeError> {
+        let mut theme = WindowTheme::default();
+        theme.load_file(path)?;
+        Ok(theme)
+    }
+
+    /// Applies `key = value` lines from a TOML-like theme file, leaving keys that aren't in it alone.
+    ///
+    /// ```toml
+    /// # Start from the colors of a built-in preset (dark, light or high_contrast)
+    /// preset = "light"
+    /// # Colors are [r, g, b, a] (0-1) or hex strings
+    /// background = [0.1, 0.1, 0.1, 1.0]
+    /// button = "#17406f"
+    /// border_radius = 7
+    /// font = "assets/Roboto.ttf"
+    /// ```
+    ///
+    /// `[sections]` are allowed for grouping, but ignored.
+    pub fn load_str(&mut self, text: &str) -> Result<(), ThemeError> {
+        for (line, key, value) in theme_lines(text)? {
+            self.set_value(key, value).map_err(|message| ThemeError { line, message })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn load_file(&mut self, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        self.load_str(&read_theme_file(path.as_ref())?)
+    }
+
+    /// Writes every metric and color in the format `load_str` reads. The font isn't included.
+    pub fn to_theme_string(&self) -> String {
+        let mut text = String::new();
+        self.write_values(&mut text);
+        text
+    }
+
+    fn set_font_path(&mut self, value: &str) -> Result<(), String> {
+        let font = UiFont::load(unquote(value)).map_err(|e| e.message)?;
+
+        self.set_font(FontStyle::Regular, Some(font));
+        Ok(())
+    }
+}
+
+/// Parses a theme file on top of the default theme. See `WindowTheme::load_str` for the format.
+impl FromStr for WindowTheme {
+    type Err = ThemeError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut theme = WindowTheme::default();
+        theme.load_str(text)?;
+        Ok(theme)
+    }
+}
+
+macro_rules! theme_values {
+    (
+        metrics: [$($metric:ident: $metric_ty:ty),* $(,)?],
+        colors: [$($variant:ident => $color:ident),* $(,)?]
+    ) => {
+        /// Names one of `WindowTheme`'s colors, e.g. for `StyleVar::Color`.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum ThemeColor {
+            $($variant,)*
         }
+
+        impl WindowTheme {
+            pub fn color(&self, color: ThemeColor) -> Color {
+                match color {
+                    $(ThemeColor::$variant => self.$color,)*
+                }
+            }
+
+            pub fn color_mut(&mut self, color: ThemeColor) -> &mut Color {
+                match color {
+                    $(ThemeColor::$variant => &mut self.$color,)*
+                }
+            }
+
+            /// Takes every color from `other`, keeping this theme's font and metrics.
+            pub fn set_colors(&mut self, other: &WindowTheme) -> &mut Self {
+                $(self.$color = other.$color;)*
+                self
+            }
+
+            fn set_value(&mut self, key: &str, value: &str) -> Result<(), String> {
+                match key {
+                    "font" => self.set_font_path(value)?,
+                    "preset" => {
+                        self.set_colors(&unquote(value).parse::<ThemePreset>()?.theme());
+                    }
+                    $(stringify!($metric) => self.$metric = parse_number::<$metric_ty>(value)?,)*
+                    $(stringify!($color) => self.$color = parse_color(value)?,)*
+                    _ => return Err(format!("unknown theme key `{key}`")),
+                }
+
+                Ok(())
+            }
+
+            /// Sets `key` to its value in `other`.
+            fn copy_value(&mut self, key: &str, other: &WindowTheme) {
+                match key {
+                    "font" => {
+                        self.font = other.font.clone();
+                        self.fonts.set(FontStyle::Regular, other.fonts.get(FontStyle::Regular).cloned());
+                    }
+                    "preset" => {
+                        self.set_colors(other);
+                    }
+                    $(stringify!($metric) => self.$metric = other.$metric,)*
+                    $(stringify!($color) => self.$color = other.$color,)*
+                    _ => {}
+                }
+            }
+
+            fn write_values(&self, text: &mut String) {
+                $(let _ = writeln!(text, "{} = {}", stringify!($metric), self.$metric);)*
+                $(let _ = writeln!(text, "{} = {}", stringify!($color), write_color(self.$color));)*
+            }
+        }
+    };
+}
+
+theme_values! {
+    metrics: [
+        title_thickness: f32,
+        scrollbar_thickness: f32,
+        holder_padding: f32,
+        border_radius: f32,
+        font_size: u16,
+        title_font_size: u16,
+        widget_padding: f32,
+        button_rounding: f32,
+        frame_rounding: f32,
+    ],
+    colors: [
+        Background => background,
+        InactiveTitlebar => inactive_titlebar,
+        ActiveTitlebar => active_titlebar,
+        TitleText => title_text,
+        TitleButtonHover => title_button_hover,
+        WinStroke => win_stroke,
+        ActiveStroke => active_stroke,
+        HoverStroke => hover_stroke,
+        CloseButton => close_button,
+        CloseButtonHover => close_button_hover,
+        CloseButtonPress => close_button_press,
+        ResizeHandle => resize_handle,
+        FocusRing => focus_ring,
+        Scrollbar => scrollbar,
+        ScrollbarHover => scrollbar_hover,
+        ScrollbarPress => scrollbar_press,
+        DockTarget => dock_target,
+        Text => text,
+        TextMuted => text_muted,
+        Button => button,
+        ButtonText => button_text,
+        ButtonStroke => button_stroke,
+        Checkbox => checkbox,
+        CheckboxHover => checkbox_hover,
+        CheckboxPress => checkbox_press,
+        CheckboxCheck => checkbox_check,
+        CheckboxMark => checkbox_mark,
+        Radio => radio,
+        RadioHover => radio_hover,
+        RadioPress => radio_press,
+        RadioDot => radio_dot,
+        Slider => slider,
+        SliderHover => slider_hover,
+        SliderPress => slider_press,
+        SliderGrab => slider_grab,
+        ProgressBar => progress_bar,
+        ProgressFill => progress_fill,
+        Dropdown => dropdown,
+        DropdownHover => dropdown_hover,
+        DropdownPress => dropdown_press,
+        DropdownButton => dropdown_button,
+        DropdownArrow => dropdown_arrow,
+        DropdownList => dropdown_list,
+        DropdownListStroke => dropdown_list_stroke,
+        DropdownItemHover => dropdown_item_hover,
+        DropdownItemPress => dropdown_item_press,
+        Textbox => textbox,
+        TextboxHover => textbox_hover,
+        TextboxStroke => textbox_stroke,
+        TextboxHoverStroke => textbox_hover_stroke,
+        TextboxEditStroke => textbox_edit_stroke,
+        Caret => caret,
+        Selection => selection,
+        Separator => separator,
+        TabBar => tab_bar,
+        TabActive => tab_active,
+        TabActiveStroke => tab_active_stroke,
+        TabUnderline => tab_underline,
+        TabHover => tab_hover,
+        TabHoverStroke => tab_hover_stroke,
+        TabPress => tab_press,
+        TabPressStroke => tab_press_stroke,
+    ]
+}
+
+/// A theme file that couldn't be read or parsed. `line` is 1-based, and 0 when the file couldn't be read at all.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThemeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            0 => write!(f, "{}", self.message),
+            line => write!(f, "line {line}: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+/// Reloads a theme file when it changes on disk. Polls the modified time instead of using OS file events.
+pub(crate) struct ThemeWatcher {
+    path: PathBuf,
+    /// What the keys set by the file were before it set them, to go back to when they're taken out of it.
+    base: WindowTheme,
+    /// The keys the file set the last time it loaded.
+    keys: Vec<String>,
+    modified: Option<SystemTime>,
+    last_check: f64,
+}
+
+impl ThemeWatcher {
+    const INTERVAL: f64 = 0.5;
+
+    pub fn new(path: PathBuf, base: WindowTheme) -> Self {
+        Self {
+            path,
+            base,
+            keys: vec![],
+            modified: None,
+            last_check: f64::NEG_INFINITY,
+        }
+    }
+
+    /// `current` with the file applied, if the file changed since the last call, checking at most every `INTERVAL`.
+    /// Only the keys in the file are changed, so anything else set on the theme since is kept.
+    pub fn poll(&mut self, time: f64, current: &WindowTheme) -> Option<Result<WindowTheme, ThemeError>> {
+        if time - self.last_check < Self::INTERVAL {
+            return None;
+        }
+        self.last_check = time;
+
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        Some(self.reload(current))
+    }
+
+    fn reload(&mut self, current: &WindowTheme) -> Result<WindowTheme, ThemeError> {
+        let text = read_theme_file(&self.path)?;
+        let keys = theme_lines(&text)?
+            .into_iter()
+            .map(|(_, key, _)| key.to_string())
+            .collect::<Vec<_>>();
+
+        let mut theme = current.clone();
+        for key in self.keys.iter().filter(|k| !keys.contains(k)) {
+            theme.copy_value(key, &self.base);
+        }
+        let mut base = self.base.clone();
+        for key in keys.iter().filter(|k| !self.keys.contains(k)) {
+            base.copy_value(key, current);
+        }
+
+        // Nothing changes unless the whole file loads, so a bad save is reverted by fixing it
+        theme.load_str(&text)?;
+        self.base = base;
+        self.keys = keys;
+        Ok(theme)
+    }
+}
+
+fn read_theme_file(path: &Path) -> Result<String, ThemeError> {
+    fs::read_to_string(path).map_err(|e| ThemeError {
+        line: 0,
+        message: format!("couldn't read {}: {e}", path.display()),
+    })
+}
+
+/// The `key = value` lines of a theme file, with their line numbers.
+fn theme_lines(text: &str) -> Result<Vec<(usize, &str, &str)>, ThemeError> {
+    let mut lines = vec![];
+
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_comment(line);
+        if line.is_empty() || (line.starts_with('[') && !line.contains('=')) {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(ThemeError {
+                line: i + 1,
+                message: format!("expected `key = value`, got `{line}`"),
+            });
+        };
+
+        lines.push((i + 1, key.trim(), value.trim()));
+    }
+
+    Ok(lines)
+}
+
+/// Cuts off a `#` comment, unless the `#` is inside quotes (like a hex color).
+fn strip_comment(line: &str) -> &str {
+    let mut in_quotes = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '#' if !in_quotes => return line[..i].trim(),
+            _ => {}
+        }
+    }
+    line.trim()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn parse_number<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, got `{value}`"))
+}
+
+/// `[r, g, b]`, `[r, g, b, a]` with 0-1 components, or `"#rrggbb"` / `"#rrggbbaa"`.
+fn parse_color(value: &str) -> Result<Color, String> {
+    let invalid = || format!("expected [r, g, b, a] or \"#rrggbbaa\", got `{value}`");
+
+    if let Some(list) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        let parts = list
+            .split(',')
+            .map(|p| p.trim().parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+
+        return match parts[..] {
+            [r, g, b] => Ok(Color::new(r, g, b, 1.0)),
+            [r, g, b, a] => Ok(Color::new(r, g, b, a)),
+            _ => Err(invalid()),
+        };
+    }
+
+    let hex = unquote(value).strip_prefix('#').ok_or_else(invalid)?;
+    let byte = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|b| u8::from_str_radix(b, 16).ok())
+            .ok_or_else(invalid)
+    };
+
+    match hex.len() {
+        6 => Ok(Color::from_rgba(byte(0)?, byte(2)?, byte(4)?, 255)),
+        8 => Ok(Color::from_rgba(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+        _ => Err(invalid()),
+    }
+}
+
+fn write_color(color: Color) -> String {
+    format!("[{}, {}, {}, {}]", color.r, color.g, color.b, color.a)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_partial_theme_only_changes_its_keys() {
+        let text = "\
+            [metrics]\n\
+            border_radius = 7 # rounder\n\
+            \n\
+            [colors]\n\
+            button = \"#ff0000\"\n\
+            text = [0, 1, 0]\n";
+        let theme = text.parse::<WindowTheme>().unwrap();
+        let default = WindowTheme::default();
+
+        assert_eq!(theme.border_radius, 7.0);
+        assert_eq!(theme.button, Color::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(theme.text, Color::new(0.0, 1.0, 0.0, 1.0));
+        assert_eq!(theme.background, default.background);
+        assert_eq!(theme.font_size, default.font_size);
+    }
+
+    #[test]
+    fn keys_after_a_preset_override_it() {
+        let theme = "preset = \"light\"\nbutton = \"#ff0000ff\""
+            .parse::<WindowTheme>()
+            .unwrap();
+
+        assert_eq!(theme.background, ThemePreset::Light.theme().background);
+        assert_eq!(theme.button, Color::new(1.0, 0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn a_written_theme_reads_back_the_same() {
+        let light = ThemePreset::Light.theme();
+        let theme = light.to_theme_string().parse::<WindowTheme>().unwrap();
+
+        assert_eq!(theme.to_theme_string(), light.to_theme_string());
+    }
+
+    #[test]
+    fn parse_errors_give_the_line() {
+        let error = |text: &str| text.parse::<WindowTheme>().err().unwrap();
+
+        let e = error("border_radius = 7\n\nfont_size = big");
+        assert_eq!(e.line, 3);
+        assert_eq!(e.message, "expected a number, got `big`");
+
+        let e = error("# comment\nbutton_color = [1, 0, 0]");
+        assert_eq!(e.line, 2);
+        assert_eq!(e.message, "unknown theme key `button_color`");
+
+        assert_eq!(error("just some text").line, 1);
+        assert_eq!(error("button = \"#12345\"").line, 1);
+        assert_eq!(error("text = [1, 0]").line, 1);
+        assert_eq!(error("preset = \"neon\"").message, "unknown theme preset `neon`");
+    }
+
+    #[test]
+    fn a_failed_load_str_stops_at_the_bad_line() {
+        let mut theme = WindowTheme::default();
+
+        assert!(theme.load_str("border_radius = 9\nfont_size = x\nwidget_padding = 20").is_err());
+        assert_eq!(theme.border_radius, 9.0);
+        assert_eq!(theme.widget_padding, WindowTheme::default().widget_padding);
+    }
+
+    #[test]
+    fn a_bad_save_leaves_the_watcher_alone() {
+        let path = std::env::temp_dir().join(format!("creeperui-watch-{}.toml", std::process::id()));
+        let current = WindowTheme::default();
+        let mut watcher = ThemeWatcher::new(path.clone(), current.clone());
+
+        fs::write(&path, "border_radius = 9").unwrap();
+        let current = watcher.reload(&current).unwrap();
+
+        let mut changed = current.clone();
+        changed.button = RED;
+        fs::write(&path, "border_radius = 9\nbutton = \"#00ff00\"\nnot_a_key = 1").unwrap();
+        assert!(watcher.reload(&changed).is_err());
+        assert_eq!(watcher.keys, vec!["border_radius"]);
+        assert_eq!(watcher.base.button, WindowTheme::default().button);
+
+        fs::write(&path, "").unwrap();
+        let theme = watcher.reload(&changed).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(theme.border_radius, WindowTheme::default().border_radius);
+        assert_eq!(theme.button, RED);
     }
 }
@@ -1,21 +1,30 @@
 use crate::text_ex::TextEx;
+use crate::ui::draw_list::DrawList;
+use crate::ui::focus::{FocusContext, NavInput};
+use crate::ui::input::UiInput;
 use crate::ui::mouse_action::WidgetAction;
+use crate::ui::windows::style::{styled_theme, StyleVar};
 use crate::widgets::*;
+use crate::{Error, WindowTheme};
 use indexmap::IndexSet;
 use macroquad::math::u16;
 use macroquad::prelude::*;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::rc::Rc;
 use std::hash::{DefaultHasher, Hash, Hasher};
-use crate::Window;
 
 pub struct RenderInfo<'a> {
     pub rect: Rect,
-    pub cam_1: &'a Camera2D,
-    pub cam_2: &'a Camera2D,
-    pub cam_3: &'a Camera2D,
     pub font: &'a Option<Font>,
+    pub theme: &'a WindowTheme,
     pub win_rect: Rect,
     pub same_line: bool,
+    /// `UiInput::time` from the last update, for animations like the caret blink.
+    pub time: f64,
+    pub draw_list: &'a mut DrawList,
+    /// Drawn on top of the whole window and not clipped to it (e.g. open dropdowns).
+    pub overlay: &'a mut DrawList,
 }
 
 pub struct UpdateInfo<'a> {
@@ -24,15 +33,26 @@ pub struct UpdateInfo<'a> {
     pub hover: bool,
     pub mouse: Vec2,
     pub font: &'a Option<Font>,
+    pub theme: &'a WindowTheme,
     pub win_rect: Rect,
     pub same_line: bool,
-}
-
-#[derive(Clone, Default)]
-pub struct GlobalRenderTargets {
-    pub target_1: Option<RenderTarget>,
-    pub target_2: Option<RenderTarget>,
-    pub target_3: Option<RenderTarget>,
+    pub input: &'a UiInput,
+
+    /// Whether this widget has keyboard focus.
+    pub focused: bool,
+    /// Navigation input meant for this widget. Only the `NavInput::broadcast` part unless it's focused.
+    pub nav: NavInput,
+    /// Set by focusable widgets to their hit rect, which puts them in the focus order.
+    pub focus_rect: Option<Rect>,
+    /// Set by a widget that wants keyboard focus (usually because it was clicked).
+    pub request_focus: bool,
+    pub focus: &'a mut FocusContext,
+    /// Passed on to widgets that hold other widgets.
+    pub id_policy: IdConflictPolicy,
+    /// The window's `push_id` stack, passed on like `id_policy`.
+    pub id_seed: u64,
+    /// This widget's focus ID, which widgets that hold others use as their holder's `focus_scope`.
+    pub focus_id: WidgetIdNum,
 }
 
 pub type WidgetIdNum = u64;
@@ -41,6 +61,19 @@ pub struct WidgetHolder {
     pub same_line: bool,
     pub(crate) widgets: HashMap<WidgetIdNum, Box<dyn Widget>>,
     pub(crate) frame_ids: IndexSet<WidgetIdNum>,
+    /// The window's style stack, handed to widgets as they're created.
+    pub(crate) style: Rc<Vec<StyleVar>>,
+    /// Styles pushed around each widget this frame. Unstyled widgets aren't in here.
+    styles: HashMap<WidgetIdNum, Rc<Vec<StyleVar>>>,
+    pub(crate) id_policy: IdConflictPolicy,
+    /// The window's `push_id` stack, mixed into every widget ID.
+    pub(crate) id_seed: u64,
+    /// Mixed into the focus IDs of this holder's widgets. Auto IDs repeat across same-line rows
+    /// and columns, but focus has to tell them apart within the whole window.
+    pub(crate) focus_scope: u64,
+    /// Widgets that had the same ID as another this frame, with the text to show on them.
+    /// Only filled in with `IdConflictPolicy::Highlight`.
+    conflicts: HashMap<WidgetIdNum, String>,
 }
 
 impl WidgetHolder {
@@ -49,54 +82,139 @@ impl WidgetHolder {
             same_line,
             widgets: HashMap::new(),
             frame_ids: IndexSet::new(),
+            style: Rc::default(),
+            styles: HashMap::new(),
+            id_policy: IdConflictPolicy::default(),
+            id_seed: 0,
+            focus_scope: 0,
+            conflicts: HashMap::new(),
         }
     }
 
     pub fn reset(&mut self) {
         self.frame_ids.clear();
+        self.styles.clear();
+        self.conflicts.clear();
+    }
+
+    fn widget_id(&mut self, widget_type: &str, id: WidgetId, label: &str) -> WidgetIdNum {
+        self.try_widget_id(widget_type, id, label)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    /// Hashes the ID of a widget created this frame. A duplicate is given a new ID,
+    /// or is an `Error::DuplicateId` if `id_policy` is `Panic`.
+    fn try_widget_id(
+        &mut self,
+        widget_type: &str,
+        id: WidgetId,
+        label: &str,
+    ) -> Result<WidgetIdNum, Error> {
+        let hash = hash_widget_id(self.id_seed, widget_type, &id, label);
+        if !self.frame_ids.contains(&hash) {
+            return Ok(hash);
+        }
+
+        if self.id_policy == IdConflictPolicy::Panic {
+            return Err(Error::DuplicateId {
+                widget_type: widget_type.to_string(),
+                label: label.to_string(),
+            });
+        }
+
+        // The nth duplicate always gets the same ID, so its state survives between frames
+        let unique = (1u32..)
+            .map(|n| {
+                let mut hasher = DefaultHasher::new();
+                (hash, n).hash(&mut hasher);
+                hasher.finish()
+            })
+            .find(|h| !self.frame_ids.contains(h))
+            .unwrap();
+
+        if self.id_policy == IdConflictPolicy::Highlight {
+            let text = match id {
+                WidgetId::Explicit(s) => format!("ID conflict: {s}"),
+                WidgetId::Auto => format!("ID conflict: {label}"),
+            };
+            self.conflicts.insert(hash, text.clone());
+            self.conflicts.insert(unique, text);
+        }
+
+        Ok(unique)
+    }
+
+    fn insert_frame_id(&mut self, id: WidgetIdNum) {
+        self.frame_ids.insert(id);
+
+        if !self.style.is_empty() {
+            self.styles.insert(id, self.style.clone());
+        }
+    }
+
+    /// The theme for one widget, with whatever styles were pushed around it.
+    fn widget_theme<'t>(&self, id: &WidgetIdNum, theme: &'t WindowTheme) -> Cow<'t, WindowTheme> {
+        match self.styles.get(id) {
+            Some(styles) => Cow::Owned(styled_theme(theme, styles)),
+            _ => Cow::Borrowed(theme),
+        }
     }
 
     pub fn retain(&mut self) {
         self.widgets.retain(|k, _| self.frame_ids.contains(k));
     }
 
-    pub fn render<'a>(
+    #[allow(clippy::too_many_arguments)]
+    pub fn render(
         &self,
         rect: &Rect,
-        scroll_y: f32,
-        font: &Option<Font>,
+        scroll: Vec2,
+        theme: &WindowTheme,
         vertical_offset: f32,
-        cameras: [&Camera2D; 3],
+        time: f64,
+        draw_list: &mut DrawList,
+        overlay: &mut DrawList,
     ) -> (f32, f32) {
-        let [cam_1, cam_2, cam_3] = cameras;
-
-        let mut holder_rect = Rect::new(rect.x, rect.y - scroll_y + vertical_offset, 0.0, 0.0);
+        let mut holder_rect = Rect::new(
+            rect.x - scroll.x,
+            rect.y - scroll.y + vertical_offset,
+            0.0,
+            0.0,
+        );
 
         for i in self.frame_ids.iter() {
-            set_camera(cam_1);
+            let theme = self.widget_theme(i, theme);
 
             let mut info = RenderInfo {
                 rect: holder_rect,
                 win_rect: *rect,
                 same_line: self.same_line,
-                font,
-                cam_1,
-                cam_2,
-                cam_3,
+                font: &theme.font,
+                theme: &theme,
+                time,
+                draw_list,
+                overlay,
             };
 
             let widget_size = self.widgets.get(i).unwrap().render(&mut info);
 
+            if let (Some(text), Some(size)) = (self.conflicts.get(i), widget_size) {
+                let y = match self.same_line {
+                    true => holder_rect.y,
+                    _ => holder_rect.y + holder_rect.h,
+                };
+                draw_id_conflict(draw_list, Rect::new(holder_rect.x, y, size.x, size.y), text);
+            }
+
             if let Some(size) = widget_size {
                 if self.same_line {
-                    let padding = 5.0;
-                    holder_rect.x += size.x + padding;
+                    holder_rect.x += size.x + theme.widget_padding;
 
                     if size.y > holder_rect.h {
                         holder_rect.h = size.y
                     }
                 } else {
-                    holder_rect.h += size.y + 5.0;
+                    holder_rect.h += size.y + theme.widget_padding;
 
                     if size.x > holder_rect.w {
                         holder_rect.w = size.x
@@ -108,51 +226,80 @@ impl WidgetHolder {
         (holder_rect.w, holder_rect.h)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn update(
         &mut self,
         rect: &Rect,
         vertical_offset: f32,
         show_titlebar: bool,
         hover: bool,
-        mouse: Vec2,
-        scroll_y: f32,
-        font: &Option<Font>,
+        scroll: Vec2,
+        theme: &WindowTheme,
         mouse_action: &mut WidgetAction,
+        input: &UiInput,
+        focus: &mut FocusContext,
     ) -> (WidgetAction, Rect) {
         let title_thickness = match show_titlebar {
             false => 0.0,
-            _ => 30.0,
+            _ => theme.title_thickness,
         };
         let mut holder_rect = Rect::new(
-            rect.x,
-            rect.y + title_thickness - scroll_y + vertical_offset,
+            rect.x - scroll.x,
+            rect.y + title_thickness - scroll.y + vertical_offset,
             0.0,
             0.0,
         );
 
+        let mut last_padding = 0.0;
+
         for i in self.frame_ids.iter() {
+            let focus_id = focus_id(self.focus_scope, *i);
+            let focused = focus.focused == Some(focus_id);
+            let theme = self.widget_theme(i, theme);
+
             let mut info = UpdateInfo {
                 rect: holder_rect, // by value
                 win_rect: *rect,   // also by value
                 same_line: self.same_line,
                 mouse_action,
                 hover,
-                mouse,
-                font,
+                mouse: input.mouse,
+                font: &theme.font,
+                theme: &theme,
+                input,
+                focused,
+                nav: match focused {
+                    true => focus.nav,
+                    _ => focus.nav.broadcast(),
+                },
+                focus_rect: None,
+                request_focus: false,
+                id_policy: self.id_policy,
+                id_seed: self.id_seed,
+                focus_id,
+                focus,
             };
 
             let widget_size = self.widgets.get_mut(i).unwrap().update(&mut info);
 
+            if let Some(focus_rect) = info.focus_rect {
+                info.focus.items.push((focus_id, focus_rect));
+            }
+            if info.request_focus {
+                info.focus.requested = Some(focus_id);
+            }
+
             if let Some(size) = widget_size {
+                last_padding = theme.widget_padding;
+
                 if self.same_line {
-                    let padding = 5.0;
-                    holder_rect.x += size.x + padding;
+                    holder_rect.x += size.x + theme.widget_padding;
 
                     if size.y > holder_rect.h {
                         holder_rect.h = size.y
                     }
                 } else {
-                    holder_rect.h += size.y + 5.0;
+                    holder_rect.h += size.y + theme.widget_padding;
 
                     if size.x > holder_rect.w {
                         holder_rect.w = size.x
@@ -161,6 +308,12 @@ impl WidgetHolder {
             }
         }
 
+        // Same-line rows grow sideways, so their width is how far along the last widget ended
+        if self.same_line {
+            holder_rect.w = (holder_rect.x - rect.x + scroll.x - last_padding).max(0.0);
+            holder_rect.x = rect.x - scroll.x;
+        }
+
         (mouse_action.clone(), holder_rect)
     }
 }
@@ -172,13 +325,14 @@ impl WidgetHolder {
 impl WidgetHolder {
     pub fn text(&mut self, id: WidgetId, label: String) -> &mut Text {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(&format!("Text:{unique}"), &self.frame_ids, id, &label);
+        let new_id = self.widget_id(&format!("Text:{unique}"), id, &label);
+        let text = split_label(&label).0.to_string();
 
-        if !self.widgets.contains_key(&new_id) {
-            let w = Text::new(label.clone());
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+        self.widgets.entry(new_id).or_insert_with(|| {
+            let w = Text::new(text.clone());
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut Text = self
@@ -188,6 +342,7 @@ impl WidgetHolder {
             .as_any_mut()
             .downcast_mut()
             .unwrap();
+        b.value = text;
         b
     }
 
@@ -200,18 +355,18 @@ impl WidgetHolder {
         font: Option<Font>,
     ) -> &mut TextEx {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(
+        let new_id = self.widget_id(
             &format!("TextEx:{unique}:{font_size}:{font:?}:{color:?}"),
-            &self.frame_ids,
             id,
             &label,
         );
+        let text = split_label(&label).0.to_string();
 
-        if !self.widgets.contains_key(&new_id) {
-            let w = TextEx::new(label.clone(), color, font_size, font);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+        self.widgets.entry(new_id).or_insert_with(|| {
+            let w = TextEx::new(text.clone(), color, font_size, font);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut TextEx = self
@@ -221,18 +376,20 @@ impl WidgetHolder {
             .as_any_mut()
             .downcast_mut()
             .unwrap();
+        b.value = text;
         b
     }
 
     pub fn button(&mut self, id: WidgetId, label: String) -> &mut Button {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(&format!("Button:{unique}"), &self.frame_ids, id, &label);
+        let new_id = self.widget_id(&format!("Button:{unique}"), id, &label);
+        let label = split_label(&label).0.to_string();
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = Button::new(label.clone());
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut Button = self
@@ -247,13 +404,14 @@ impl WidgetHolder {
     }
 
     pub fn checkbox(&mut self, id: WidgetId, label: String, value: bool) -> &mut Checkbox {
-        let new_id = create_widget_id("Checkbox", &self.frame_ids, id, "");
+        let new_id = self.widget_id("Checkbox", id, id_part(&label));
+        let label = split_label(&label).0.to_string();
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = Checkbox::new(label.clone(), value);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut Checkbox = self
@@ -273,25 +431,37 @@ impl WidgetHolder {
         path: String,
         size: Option<Vec2>,
     ) -> &mut ImageWidget {
+        self.try_image(id, path, size)
+            .await
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub async fn try_image(
+        &mut self,
+        id: WidgetId,
+        path: String,
+        size: Option<Vec2>,
+    ) -> Result<&mut ImageWidget, Error> {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(&format!("Image:{unique}"), &self.frame_ids, id, &path);
+        let new_id = self.try_widget_id(&format!("Image:{unique}"), id, &path)?;
 
-        if !self.widgets.contains_key(&new_id) {
-            let w = ImageWidget::new(path, size).await;
-            self.widgets.insert(new_id, Box::new(w));
+        if let std::collections::hash_map::Entry::Vacant(e) = self.widgets.entry(new_id) {
+            let w = ImageWidget::try_new(path, size).await?;
+            e.insert(Box::new(w));
         }
-        self.frame_ids.insert(new_id);
+        self.insert_frame_id(new_id);
 
-        self.widgets
+        Ok(self
+            .widgets
             .get_mut(&new_id)
             .unwrap()
             .as_any_mut()
             .downcast_mut()
-            .unwrap()
+            .unwrap())
     }
 
     pub fn slider(&mut self, id: WidgetId, label: String, slider_info: SliderInfo) -> &mut Slider {
-        let new_id = create_widget_id(
+        let new_id = self.widget_id(
             &format!(
                 "Slider<{}>",
                 match slider_info {
@@ -299,16 +469,16 @@ impl WidgetHolder {
                     SliderInfo::Float { .. } => "Float",
                 }
             ),
-            &self.frame_ids,
             id,
             &label,
         );
+        let label = split_label(&label).0.to_string();
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = Slider::new(label.clone(), slider_info);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut Slider = self
@@ -329,13 +499,13 @@ impl WidgetHolder {
         default_value: String,
     ) -> &mut Dropdown {
         let label = items.join("|");
-        let new_id = create_widget_id("Dropdown", &self.frame_ids, id, &label);
+        let new_id = self.widget_id("Dropdown", id, &label);
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = Dropdown::new(items, default_value);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut Dropdown = self
@@ -356,13 +526,13 @@ impl WidgetHolder {
         default_value: String,
     ) -> &mut RadioButtons {
         let label = options.join("|");
-        let new_id = create_widget_id("RadioButtons", &self.frame_ids, id, &label);
+        let new_id = self.widget_id("RadioButtons", id, &label);
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = RadioButtons::new(options, default_value);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut RadioButtons = self
@@ -378,13 +548,13 @@ impl WidgetHolder {
 
     pub fn separator(&mut self, id: WidgetId) -> &mut Separator {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(&format!("Separator:{unique}"), &self.frame_ids, id, unique);
+        let new_id = self.widget_id(&format!("Separator:{unique}"), id, unique);
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = Separator::new();
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         let b: &mut Separator = self
             .widgets
@@ -404,7 +574,7 @@ impl WidgetHolder {
         progress_info: ProgressInfo,
     ) -> &mut ProgressBar {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(
+        let new_id = self.widget_id(
             &format!(
                 "ProgressBar<{}>:{unique}",
                 match progress_info {
@@ -412,16 +582,16 @@ impl WidgetHolder {
                     ProgressInfo::Float { .. } => "Float",
                 }
             ),
-            &self.frame_ids,
             id,
             &label,
         );
+        let label = split_label(&label).0.to_string();
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = ProgressBar::new(label.clone(), progress_info);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut ProgressBar = self
@@ -436,13 +606,13 @@ impl WidgetHolder {
     }
 
     pub fn tabs(&mut self, id: WidgetId, tabs: Vec<String>, default_tab: usize) -> &mut TabHolder {
-        let new_id = create_widget_id("TabHolder", &self.frame_ids, id, &tabs.join("|"));
+        let new_id = self.widget_id("TabHolder", id, &tabs.join("|"));
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = TabHolder::new(tabs, default_tab);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut TabHolder = self
@@ -457,13 +627,13 @@ impl WidgetHolder {
 
     pub fn textbox(&mut self, id: WidgetId, text: String) -> &mut TextBox {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(&format!("TextBox:{unique}"), &self.frame_ids, id, "");
+        let new_id = self.widget_id(&format!("TextBox:{unique}"), id, "");
 
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = TextBox::new(text.clone(), None);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
 
         // UPDATE STATE
         let b: &mut TextBox = self
@@ -478,13 +648,14 @@ impl WidgetHolder {
     
     pub fn labeled_textbox(&mut self, id: WidgetId, label: String, text: String) -> &mut TextBox {
         let unique = &self.frame_ids.len().to_string();
-        let new_id = create_widget_id(&format!("LabeledTextBox:{unique}"), &self.frame_ids, id, "");
+        let new_id = self.widget_id(&format!("LabeledTextBox:{unique}"), id, id_part(&label));
+        let label = split_label(&label).0.to_string();
         
-        if !self.widgets.contains_key(&new_id) {
-            let w = TextBox::new(text.clone(), Some(label));
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+        self.widgets.entry(new_id).or_insert_with(|| {
+            let w = TextBox::new(text.clone(), Some(label.clone()));
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
         
         // UPDATE STATE
         let b: &mut TextBox = self
@@ -494,17 +665,82 @@ impl WidgetHolder {
             .as_any_mut()
             .downcast_mut()
             .unwrap();
+        b.label = Some(label);
+        b
+    }
+    
+    pub fn input_text_with_filter(
+        &mut self,
+        id: WidgetId,
+        filter: impl Fn(char) -> bool + 'static,
+    ) -> &mut TextBox {
+        let b = self.textbox(id, String::new());
+        b.set_filter(filter);
+        b
+    }
+
+    pub fn input_number<T: InputNumber>(
+        &mut self,
+        id: WidgetId,
+        label: String,
+        default_value: T,
+    ) -> &mut NumberInput<T> {
+        let unique = &self.frame_ids.len().to_string();
+        let new_id = self.widget_id(
+            &format!("NumberInput<{}>:{unique}", std::any::type_name::<T>()),
+            id,
+            &label,
+        );
+        let label = split_label(&label).0.to_string();
+
+        self.widgets.entry(new_id).or_insert_with(|| {
+            let w = NumberInput::new(default_value, Some(label.clone()));
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
+
+        // UPDATE STATE
+        let b: &mut NumberInput<T> = self
+            .widgets
+            .get_mut(&new_id)
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap();
+        b.textbox().label = Some(label);
+        b
+    }
+
+    pub fn text_editor(&mut self, id: WidgetId, text: &mut String) -> &mut TextEditor {
+        let unique = &self.frame_ids.len().to_string();
+        let new_id = self.widget_id(&format!("TextEditor:{unique}"), id, "");
+
+        self.widgets.entry(new_id).or_insert_with(|| {
+            let w = TextEditor::new(text.clone());
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
+
+        // UPDATE STATE
+        let b: &mut TextEditor = self
+            .widgets
+            .get_mut(&new_id)
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut()
+            .unwrap();
+        b.sync(text);
         b
     }
     
     pub fn column(&mut self, id: WidgetId, f: impl FnMut(&mut Column) + 'static) -> &mut Column {
-        let new_id = create_widget_id("Column", &self.frame_ids, id, "");
+        let new_id = self.widget_id("Column", id, "");
         
-        if !self.widgets.contains_key(&new_id) {
+        self.widgets.entry(new_id).or_insert_with(|| {
             let w = Column::new(f);
-            self.widgets.insert(new_id, Box::new(w));
-        }
-        self.frame_ids.insert(new_id);
+            Box::new(w)
+        });
+        self.insert_frame_id(new_id);
         
         // UPDATE STATE
         let b: &mut Column = self
@@ -518,14 +754,28 @@ impl WidgetHolder {
     }
 }
 
-pub fn create_widget_id(
-    widget_type: &str,
-    frame_ids: &IndexSet<WidgetIdNum>,
-    id: WidgetId,
-    label: &str,
-) -> WidgetIdNum {
-    // Generate a hash based on a widget type + label + explicit/auto ID
+/// The label to hash for widgets that keep their ID when their text changes:
+/// only labels with a `##` or `###` ID part count.
+fn id_part(label: &str) -> &str {
+    match label.contains("##") {
+        true => label,
+        _ => "",
+    }
+}
+
+/// A widget's ID within the whole window, for `FocusState`.
+pub(crate) fn focus_id(scope: u64, id: WidgetIdNum) -> WidgetIdNum {
     let mut hasher = DefaultHasher::new();
+    scope.hash(&mut hasher);
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn hash_widget_id(seed: u64, widget_type: &str, id: &WidgetId, label: &str) -> WidgetIdNum {
+    // Generate a hash based on the ID stack + widget type + label + explicit/auto ID
+    let (_, label) = split_label(label);
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
     widget_type.hash(&mut hasher);
 
     match id {
@@ -536,11 +786,30 @@ pub fn create_widget_id(
         }
     };
 
-    let hash = hasher.finish();
-
-    if frame_ids.contains(&hash) {
-        panic!("Widget with this type/label already exists. Please give a unique explicit ID.");
-    }
+    hasher.finish()
+}
 
-    hash
+/// The `IdConflictPolicy::Highlight` overlay: a red box around the widget, labeled with its ID.
+fn draw_id_conflict(draw_list: &mut DrawList, rect: Rect, text: &str) {
+    draw_list.draw_rectangle(rect.x, rect.y, rect.w, rect.h, RED.with_alpha(0.25));
+    draw_list.draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, RED);
+
+    let text_dim = draw_list.fonts().measure_text(text, None, 12, 1.0);
+    draw_list.draw_rectangle(
+        rect.x,
+        rect.y - text_dim.height - 4.0,
+        text_dim.width + 6.0,
+        text_dim.height + 4.0,
+        RED,
+    );
+    draw_list.draw_text_ex(
+        text,
+        rect.x + 3.0,
+        rect.y - 3.0,
+        TextParams {
+            font_size: 12,
+            color: WHITE,
+            ..Default::default()
+        },
+    );
 }
@@ -6,6 +6,33 @@ pub enum WidgetId {
     Explicit(String),
 }
 
+/// What to do when two widgets end up with the same ID in one window and frame,
+/// e.g. two checkboxes with the same label made in a loop.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IdConflictPolicy {
+    #[default]
+    Panic,
+    /// Give the duplicate a new ID based on how many came before it, so it keeps its state between frames.
+    Disambiguate,
+    /// Disambiguate, and outline every conflicting widget in red with its ID.
+    Highlight,
+}
+
+/// Splits an imgui-style label into the text to show and the part that identifies the widget.
+///
+/// `"Save##file"` shows "Save" and is identified by the whole string, so two "Save" buttons can live side by side.
+/// `"Play###toggle"` is identified by "###toggle" alone, so the text in front can change without the widget losing its state.
+pub fn split_label(label: &str) -> (&str, &str) {
+    if let Some(i) = label.find("###") {
+        return (&label[..i], &label[i..]);
+    }
+
+    match label.find("##") {
+        Some(i) => (&label[..i], label),
+        _ => (label, label),
+    }
+}
+
 impl From<()> for WidgetId {
     fn from(_: ()) -> Self {
         WidgetId::Auto
@@ -3,7 +3,6 @@ use crate::widgets::widget_holder::{RenderInfo, UpdateInfo};
 use macroquad::prelude::*;
 use std::any::Any;
 use std::ops::{Add, Deref};
-use crate::misc::rounded_rect::{draw_rounded_rect, draw_rounded_rect_stroke};
 
 pub struct Button {
     pub value: String,
@@ -11,8 +10,8 @@ pub struct Button {
     pub pressed: bool,
     pub clicked: bool,
     
-    background: Color,
-    foreground: Color,
+    background: Option<Color>,
+    foreground: Option<Color>,
 }
 
 impl Button {
@@ -23,18 +22,18 @@ impl Button {
             pressed: false,
             clicked: false,
             
-            background: Color::new(0.09, 0.25, 0.45, 1.0),
-            foreground: WHITE,
+            background: None,
+            foreground: None,
         }
     }
     
     pub fn set_background(&mut self, color: Color) -> &mut Self {
-        self.background = color;
+        self.background = Some(color);
         self
     }
     
     pub fn set_foreground(&mut self, color: Color) -> &mut Self {
-        self.foreground = color;
+        self.foreground = Some(color);
         self
     }
 }
@@ -56,13 +55,13 @@ impl Widget for Button {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.value.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -71,48 +70,50 @@ impl Widget for Button {
             _ => info.rect.h
         };
 
+        let background = self.background.unwrap_or(info.theme.button);
+
         if self.pressed {
-            draw_rounded_rect_stroke(
+            info.draw_list.draw_rounded_rect_stroke(
                 info.rect.x,
                 info.rect.y + vertical_height + 5.0,
                 text_dim.width + 10.0,
                 text_dim.height + 10.0,
-                3.0,
+                info.theme.button_rounding,
                 1.0,
-                Color::new(1.0, 1.0, 1.0, 1.0),
+                info.theme.button_stroke,
                 match (self.hovered, self.pressed) {
-                    (true, false) => Color::from_vec(self.background.to_vec().add(vec4(0.13, 0.13, 0.13, 0.0))), // HOVER
-                    (_, true) => Color::from_vec(self.background.to_vec().add(vec4(0.25, 0.25, 0.25, 0.1))),     // PRESSED
-                    _ => self.background,
+                    (true, false) => Color::from_vec(background.to_vec().add(vec4(0.13, 0.13, 0.13, 0.0))), // HOVER
+                    (_, true) => Color::from_vec(background.to_vec().add(vec4(0.25, 0.25, 0.25, 0.1))),     // PRESSED
+                    _ => background,
                 },
             );
         } else {
-            draw_rounded_rect(
+            info.draw_list.draw_rounded_rect(
                 info.rect.x,
                 info.rect.y + vertical_height + 5.0,
                 text_dim.width + 10.0,
                 text_dim.height + 10.0,
-                3.0,
+                info.theme.button_rounding,
                 match (self.hovered, self.pressed) {
-                    (true, false) => Color::from_vec(self.background.to_vec().add(vec4(0.13, 0.13, 0.13, 0.0))), // HOVER
-                    (_, true) => Color::from_vec(self.background.to_vec().add(vec4(0.25, 0.25, 0.25, 0.1))),     // PRESSED
-                    _ => self.background,
+                    (true, false) => Color::from_vec(background.to_vec().add(vec4(0.13, 0.13, 0.13, 0.0))), // HOVER
+                    (_, true) => Color::from_vec(background.to_vec().add(vec4(0.25, 0.25, 0.25, 0.1))),     // PRESSED
+                    _ => background,
                 },
             );
         }
 
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.value.to_string(),
                 info.rect.x + 5.0,
                 info.rect.y + text_dim.height * 2.0 + vertical_height - 3.0,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
-                    color: self.foreground,
+                    font_size: info.theme.font_size,
+                    color: self.foreground.unwrap_or(info.theme.button_text),
                     ..Default::default()
                 },
             );
@@ -122,13 +123,13 @@ impl Widget for Button {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.value.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -146,21 +147,28 @@ impl Widget for Button {
 
         if rect.contains(info.mouse) && info.hover && !info.mouse_action.taken {
             self.hovered = true;
-            if is_mouse_button_pressed(MouseButton::Left) {
+            if info.input.is_mouse_button_pressed(MouseButton::Left) {
                 self.pressed = true;
+                info.request_focus = true;
             }
         } else {
             self.hovered = false;
         }
 
         self.clicked = false;
-        if is_mouse_button_released(MouseButton::Left) {
+        if info.input.is_mouse_button_released(MouseButton::Left) {
             if self.pressed && self.hovered {
                 self.clicked = true;
             }
             self.pressed = false;
         }
 
+        if info.nav.activate {
+            self.clicked = true;
+        }
+
+        info.focus_rect = Some(rect);
+
         Some(vec2(text_dim.width + 10.0, text_dim.height + 10.0))
     }
 }
@@ -32,23 +32,23 @@ impl Widget for Checkbox {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.text.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
-        let char_dim = measure_text(
+        let char_dim = info.theme.fonts.measure_text(
             "A",
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -58,73 +58,75 @@ impl Widget for Checkbox {
         };
         
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.text.to_string(),
                 info.rect.x + text_dim.height + 10.0,
                 info.rect.y + text_dim.height + vertical_height + 2.0,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
+                    font_size: info.theme.font_size,
                     color: match self.hovered {
-                        true => WHITE,
-                        _ => Color::new(0.9, 0.9, 0.9, 0.9),
+                        true => info.theme.text,
+                        _ => info.theme.text_muted,
                     },
                     ..Default::default()
                 },
             );
         }
 
-        draw_rectangle(
+        info.draw_list.draw_rounded_rect(
             info.rect.x,
             info.rect.y + vertical_height,
             char_dim.height + 5.0,
             char_dim.height + 5.0,
+            info.theme.frame_rounding,
             match (self.hovered, self.pressed) {
-                (true, false) => Color::new(0.2, 0.4, 0.6, 1.0),
-                (_, true) => Color::new(0.3, 0.5, 0.75, 1.0),
-                _ => Color::new(0.05, 0.2, 0.4, 1.0),
+                (true, false) => info.theme.checkbox_hover,
+                (_, true) => info.theme.checkbox_press,
+                _ => info.theme.checkbox,
             },
         );
 
         if self.pressed {
-            draw_rectangle_lines(
+            info.draw_list.draw_rectangle_lines(
                 info.rect.x,
                 info.rect.y + vertical_height,
                 char_dim.height + 5.0,
                 char_dim.height + 5.0,
                 2.0,
-                WHITE,
+                info.theme.checkbox_mark,
             );
         }
 
         if self.value {
-            draw_rectangle(
+            info.draw_list.draw_rounded_rect(
                 info.rect.x + 1.0,
                 info.rect.y + vertical_height + 1.0,
                 char_dim.height + 3.0,
                 char_dim.height + 3.0,
-                Color::new(0.3, 0.7, 1.0, 1.0),
+                info.theme.frame_rounding,
+                info.theme.checkbox_check,
             );
 
-            draw_line(
+            info.draw_list.draw_line(
                 info.rect.x + 4.0,
                 info.rect.y + vertical_height + 8.0,
                 info.rect.x + 6.0,
                 info.rect.y + char_dim.height + vertical_height + 3.0,
                 3.0,
-                WHITE,
+                info.theme.checkbox_mark,
             );
 
-            draw_line(
+            info.draw_list.draw_line(
                 info.rect.x + 6.0,
                 info.rect.y + char_dim.height + vertical_height + 3.0,
                 info.rect.x + char_dim.height + 2.0,
                 info.rect.y + vertical_height + 2.0,
                 3.0,
-                WHITE,
+                info.theme.checkbox_mark,
             )
         }
 
@@ -135,27 +137,27 @@ impl Widget for Checkbox {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let mut text_dim = measure_text(
+        let mut text_dim = info.theme.fonts.measure_text(
             &self.text.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
-        if self.text.len() > 0 {
+        if !self.text.is_empty() {
             text_dim.width += 5.0;
         }
         
-        let char_dim = measure_text(
+        let char_dim = info.theme.fonts.measure_text(
             "A",
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -173,25 +175,32 @@ impl Widget for Checkbox {
 
         if rect.contains(info.mouse) && info.hover && !info.mouse_action.taken {
             self.hovered = true;
-            if is_mouse_button_pressed(MouseButton::Left) {
+            if info.input.is_mouse_button_pressed(MouseButton::Left) {
                 self.pressed = true;
+                info.request_focus = true;
             }
         } else {
             self.hovered = false;
         }
 
         self.clicked = false;
-        if is_mouse_button_released(MouseButton::Left) {
+        if info.input.is_mouse_button_released(MouseButton::Left) {
             if self.pressed && self.hovered {
                 self.clicked = true;
             }
             self.pressed = false;
         }
 
+        if info.nav.activate {
+            self.clicked = true;
+        }
+
         if self.clicked {
             self.value = !self.value;
         }
 
+        info.focus_rect = Some(rect);
+
         Some(vec2(
             text_dim.width + char_dim.height + 10.0,
             char_dim.height + 10.0,
@@ -5,9 +5,11 @@ use macroquad::prelude::*;
 use std::any::Any;
 use std::hash::{DefaultHasher, Hash, Hasher};
 
+type ColumnFn = Box<dyn FnMut(&mut Column)>;
+
 pub struct Column {
     holder: WidgetHolder,
-    func: Option<Box<dyn FnMut(&mut Column)>>,
+    func: Option<ColumnFn>,
 }
 
 impl Column {
@@ -35,10 +37,12 @@ impl Widget for Column {
             self.holder
                 .render(
                     &new_rect,
+                    Vec2::ZERO,
+                    info.theme,
                     0.0,
-                    info.font,
-                    0.0,
-                    [info.cam_1, info.cam_2, info.cam_3],
+                    info.time,
+                    info.draw_list,
+                    info.overlay,
                 )
                 .into(),
         )
@@ -46,6 +50,9 @@ impl Widget for Column {
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
         self.holder.reset();
+        self.holder.id_policy = info.id_policy;
+        self.holder.id_seed = info.id_seed;
+        self.holder.focus_scope = info.focus_id;
         
         if let Some(mut f) = self.func.take() {
             f(self);
@@ -61,10 +68,11 @@ impl Widget for Column {
                 0.0,
                 false,
                 info.hover,
-                info.mouse,
-                0.0,
-                info.font,
+                Vec2::ZERO,
+                info.theme,
                 info.mouse_action,
+                info.input,
+                info.focus,
             )
             .1;
 
@@ -232,4 +240,40 @@ impl Column {
             default_text.to_string(),
         )
     }
+
+    /// A textbox that only lets in characters `filter` returns `true` for.
+    pub fn input_text_with_filter(
+        &mut self,
+        id: impl Into<WidgetId>,
+        filter: impl Fn(char) -> bool + 'static,
+    ) -> &mut TextBox {
+        self.last_widget_holder()
+            .input_text_with_filter(id.into(), filter)
+    }
+
+    /// A field for whole numbers, with -/+ buttons. Use `.value` to get the number.
+    pub fn input_int(
+        &mut self,
+        id: impl Into<WidgetId>,
+        label: impl ToString,
+        default_value: i64,
+    ) -> &mut InputInt {
+        self.last_widget_holder()
+            .input_number(id.into(), label.to_string(), default_value)
+    }
+
+    /// Like `input_int`, for `f64`s.
+    pub fn input_float(
+        &mut self,
+        id: impl Into<WidgetId>,
+        label: impl ToString,
+        default_value: f64,
+    ) -> &mut InputFloat {
+        self.last_widget_holder()
+            .input_number(id.into(), label.to_string(), default_value)
+    }
+
+    pub fn text_editor(&mut self, id: impl Into<WidgetId>, text: &mut String) -> &mut TextEditor {
+        self.last_widget_holder().text_editor(id.into(), text)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::ui::focus::NavDirection;
 use crate::widgets::widget::Widget;
 use crate::widgets::widget_holder::{RenderInfo, UpdateInfo};
 use macroquad::input::MouseButton::Left;
@@ -34,6 +35,78 @@ impl Dropdown {
     }
 }
 
+impl Dropdown {
+    // Enter/Space/A opens the list, Up/Down walk through it, Enter picks an item and Escape/B closes it.
+    // While closed, Up/Down change the value directly.
+    fn update_keyboard(&mut self, info: &mut UpdateInfo, text_dim: TextDimensions) {
+        if self.items.is_empty() {
+            return;
+        }
+
+        let last = self.items.len() - 1;
+        let step = |i: usize, up: bool| match up {
+            true => i.saturating_sub(1),
+            _ => (i + 1).min(last),
+        };
+
+        if !self.open {
+            if info.nav.activate {
+                self.open = true;
+                self.item_hovered = Some(self.value.clone());
+            } else if info.nav.up || info.nav.down {
+                let i = match self.items.iter().position(|x| *x == self.value) {
+                    Some(i) => step(i, info.nav.up),
+                    _ => 0,
+                };
+                self.value = self.items[i].clone();
+            }
+            return;
+        }
+
+        // The D-pad walks through the list instead of moving focus while it's open
+        let (up, down) = match info.nav.move_focus {
+            Some(NavDirection::Up) => (true, false),
+            Some(NavDirection::Down) => (false, true),
+            _ => (info.nav.up, info.nav.down),
+        };
+        if info.nav.move_focus.is_some() {
+            info.focus.nav_used = true;
+        }
+
+        if up || down {
+            let current = self
+                .item_hovered
+                .as_ref()
+                .and_then(|h| self.items.iter().position(|x| x == h));
+            let i = match current {
+                Some(i) => step(i, up),
+                _ => self.items.iter().position(|x| *x == self.value).unwrap_or(0),
+            };
+            self.item_hovered = Some(self.items[i].clone());
+
+            // Keep the highlighted item in view
+            let item_h = text_dim.height + 10.0;
+            let list_h = (item_h * self.items.len() as f32 + 10.0).min(120.0);
+            let item_top = item_h * i as f32 + 5.0;
+            if item_top + self.scroll_y < 0.0 {
+                self.scroll_y = -item_top;
+            } else if item_top + item_h + self.scroll_y > list_h {
+                self.scroll_y = list_h - item_top - item_h;
+            }
+        }
+
+        if info.nav.activate {
+            if let Some(item) = &self.item_hovered {
+                self.value = item.clone();
+            }
+            self.open = false;
+        } else if info.nav.cancel {
+            self.open = false;
+            info.focus.nav_used = true;
+        }
+    }
+}
+
 impl Widget for Dropdown {
     fn as_any(&self) -> &dyn Any {
         self
@@ -43,13 +116,13 @@ impl Widget for Dropdown {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &"A".repeat(self.items.iter().map(|x| x.len()).max().unwrap()),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -58,183 +131,161 @@ impl Widget for Dropdown {
             _ => info.rect.h
         };
 
-        draw_rectangle(
+        info.draw_list.draw_rounded_rect(
             info.rect.x,
             info.rect.y + vertical_height,
             text_dim.width + 10.0,
             text_dim.height + 10.0,
+            info.theme.frame_rounding,
             match (self.hovered, self.pressed) {
-                (true, false) => Color::new(0.2, 0.4, 0.7, 0.9), // HOVER
-                (_, true) => Color::new(0.3, 0.5, 0.8, 1.0),     // PRESSED
-                _ => Color::new(0.1, 0.3, 0.5, 0.9),
+                (true, false) => info.theme.dropdown_hover,
+                (_, true) => info.theme.dropdown_press,
+                _ => info.theme.dropdown,
             },
         );
 
-        draw_rectangle(
+        info.draw_list.draw_rectangle(
             info.rect.x + text_dim.width + 10.0,
             info.rect.y + vertical_height,
             text_dim.height + 10.0,
             text_dim.height + 10.0,
-            Color::new(0.4, 0.7, 1.0, 1.0),
+            info.theme.dropdown_button,
         );
 
-        draw_line(
+        info.draw_list.draw_line(
             info.rect.x + text_dim.width + 15.0,
             info.rect.y + vertical_height + 9.0,
             info.rect.x + text_dim.width + 20.0,
             info.rect.y + vertical_height + 15.0,
             2.0,
-            WHITE,
+            info.theme.dropdown_arrow,
         );
 
-        draw_line(
+        info.draw_list.draw_line(
             info.rect.x + text_dim.width + 20.0,
             info.rect.y + vertical_height + 15.0,
             info.rect.x + text_dim.width + 24.0,
             info.rect.y + vertical_height + 9.0,
             2.0,
-            WHITE,
+            info.theme.dropdown_arrow,
         );
 
-        let value_dim = measure_text(
+        let value_dim = info.theme.fonts.measure_text(
             &self.value,
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.value,
                 info.rect.x + (text_dim.width - value_dim.width + 10.0) / 2.0,
                 info.rect.y + vertical_height + text_dim.height * 2.0 - 5.0,
                 TextParams {
-                    color: WHITE,
+                    color: info.theme.text,
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
+                    font_size: info.theme.font_size,
                     ..Default::default()
                 },
             );
         }
 
         if self.open {
-            let scale = 2.0 / (text_dim.width + 10.0);
-            let (zoom_x, zoom_y) = (scale, scale * (text_dim.width + 10.0) / 120.0);
+            let drop_rect = Rect::new(
+                info.rect.x,
+                info.rect.y + vertical_height + text_dim.height + 10.0,
+                text_dim.width + 10.0,
+                ((text_dim.height + 10.0) * self.items.len() as f32 + 10.0).clamp(0.0, 120.0),
+            );
 
-            let target = render_target(text_dim.width as u32 + 10, 120);
-            set_camera(&Camera2D {
-                zoom: vec2(zoom_x, zoom_y),
-                target: vec2(1.0 / zoom_x, 1.0 / zoom_y),
-                render_target: Some(target.clone()),
-                ..Default::default()
-            });
+            info.overlay.draw_rectangle(
+                drop_rect.x,
+                drop_rect.y,
+                drop_rect.w,
+                drop_rect.h,
+                info.theme.dropdown_list,
+            );
+
+            info.overlay.draw_rectangle_lines(
+                drop_rect.x,
+                drop_rect.y,
+                drop_rect.w,
+                drop_rect.h,
+                2.0,
+                info.theme.dropdown_list_stroke,
+            );
+
+            info.overlay.set_clip(Some(drop_rect));
 
             for i in 0..self.items.len() {
-                let item_text_dim = measure_text(
+                let item_text_dim = info.theme.fonts.measure_text(
                     &self.items[i],
                     match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    14,
+                    info.theme.font_size,
                     1.0,
                 );
 
                 if let Some(x) = &self.item_hovered {
                     if x == &self.items[i] {
-                        draw_rectangle(
-                            2.0,
-                            (text_dim.height + 10.0) * i as f32 + 5.0 + self.scroll_y,
+                        info.overlay.draw_rectangle(
+                            drop_rect.x + 2.0,
+                            drop_rect.y + (text_dim.height + 10.0) * i as f32 + 5.0 + self.scroll_y,
                             text_dim.width + 7.0,
                             text_dim.height + 10.0,
                             match self.item_pressed {
-                                true => Color::new(0.2, 0.4, 0.6, 1.0),
-                                _ => Color::new(0.15, 0.15, 0.15, 1.0),
+                                true => info.theme.dropdown_item_press,
+                                _ => info.theme.dropdown_item_hover,
                             },
                         )
                     }
                 }
 
                 for _ in 0..4 {
-                    draw_text_ex(
+                    info.overlay.draw_text_ex(
                         &self.items[i],
-                        (text_dim.width - item_text_dim.width) / 2.0 + 5.0,
-                        (text_dim.height + 10.0) * i as f32
+                        drop_rect.x + (text_dim.width - item_text_dim.width) / 2.0 + 5.0,
+                        drop_rect.y
+                            + (text_dim.height + 10.0) * i as f32
                             + text_dim.height
                             + 10.0
                             + self.scroll_y,
                         TextParams {
-                            color: WHITE,
+                            color: info.theme.text,
                             font: match &info.font {
-                                Some(f) => Some(&f),
+                                Some(f) => Some(f),
                                 _ => None,
                             },
-                            font_size: 14,
+                            font_size: info.theme.font_size,
                             ..Default::default()
                         },
                     );
                 }
             }
 
-            set_camera(info.cam_3);
-
-            draw_rectangle(
-                info.rect.x,
-                info.rect.y + vertical_height + text_dim.height + 10.0,
-                text_dim.width + 10.0,
-                ((text_dim.height + 10.0) * self.items.len() as f32 + 10.0)
-                    .max(0.0)
-                    .min(120.0),
-                BLACK,
-            );
-
-            draw_rectangle_lines(
-                info.rect.x,
-                info.rect.y + vertical_height + text_dim.height + 10.0,
-                text_dim.width + 10.0,
-                ((text_dim.height + 10.0) * self.items.len() as f32 + 10.0)
-                    .max(0.0)
-                    .min(120.0),
-                2.0,
-                DARKGRAY,
-            );
-
-            draw_texture_ex(
-                &target.texture,
-                info.rect.x,
-                info.rect.y + vertical_height + text_dim.height + 10.0,
-                WHITE,
-                DrawTextureParams {
-                    source: Some(Rect::new(
-                        0.0,
-                        0.0,
-                        (text_dim.width + 10.0).max(0.0),
-                        (120f32).max(0.0),
-                    )),
-                    ..Default::default()
-                },
-            );
-
-            set_camera(info.cam_1);
+            info.overlay.set_clip(None);
         }
 
         Some(vec2(text_dim.width + text_dim.height * 2.0 + 10.0, text_dim.height + 10.0))
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &"A".repeat(self.items.iter().map(|x| x.len()).max().unwrap()),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -252,8 +303,9 @@ impl Widget for Dropdown {
 
         if rect.contains(info.mouse) && info.hover && !info.mouse_action.taken  {
             self.hovered = true;
-            if is_mouse_button_pressed(Left) {
+            if info.input.is_mouse_button_pressed(Left) {
                 self.pressed = true;
+                info.request_focus = true;
             }
         } else {
             self.hovered = false;
@@ -267,7 +319,7 @@ impl Widget for Dropdown {
                 ((text_dim.height + 10.0) * self.items.len() as f32 + 10.0).min(120.0),
             );
             if drop_rect.contains(info.mouse) {
-                let wheel = mouse_wheel();
+                let wheel = info.input.mouse_wheel();
                 if wheel.1 != 0.0 {
                     self.scroll_y += wheel.1;
                 } else if wheel.0 != 0.0 {
@@ -290,14 +342,14 @@ impl Widget for Dropdown {
 
             if drop_rect.contains(info.mouse) {
                 info.mouse_action.taken = true;
-                let mut base_item_rect = drop_rect.clone();
+                let mut base_item_rect = drop_rect;
                 base_item_rect.h = text_dim.height + 10.0;
 
-                if is_mouse_button_pressed(Left) {
+                if info.input.is_mouse_button_pressed(Left) {
                     self.item_pressed = true;
                 }
 
-                if is_mouse_button_released(Left)
+                if info.input.is_mouse_button_released(Left)
                     && self.item_pressed
                     && self.item_hovered.is_some()
                 {
@@ -315,7 +367,7 @@ impl Widget for Dropdown {
             }
         }
 
-        if is_mouse_button_released(Left) {
+        if info.input.is_mouse_button_released(Left) {
             if self.pressed && !self.open {
                 self.open = true;
                 self.item_hovered = None;
@@ -326,6 +378,9 @@ impl Widget for Dropdown {
             self.item_pressed = false;
         }
 
+        self.update_keyboard(info, text_dim);
+        info.focus_rect = Some(rect);
+
         Some(vec2(text_dim.width + text_dim.height * 2.0 + 10.0, text_dim.height + 10.0))
     }
 }
@@ -1,4 +1,5 @@
 use crate::widgets::widget::Widget;
+use crate::Error;
 use crate::widgets::widget_holder::{RenderInfo, UpdateInfo};
 use macroquad::prelude::*;
 use std::any::Any;
@@ -10,10 +11,16 @@ pub struct ImageWidget {
 
 impl ImageWidget {
     pub async fn new(path: String, size: Option<Vec2>) -> Self {
-        Self {
-            size,
-            texture: load_texture(&path).await.unwrap(),
-        }
+        Self::try_new(path, size).await.unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub async fn try_new(path: String, size: Option<Vec2>) -> Result<Self, Error> {
+        let texture = load_texture(&path).await.map_err(|e| Error::Texture {
+            message: e.to_string(),
+            path,
+        })?;
+
+        Ok(Self { size, texture })
     }
 }
 
@@ -31,7 +38,7 @@ impl Widget for ImageWidget {
             _ => info.rect.h
         };
         
-        draw_texture_ex(
+        info.draw_list.draw_texture_ex(
             &self.texture,
             info.rect.x,
             info.rect.y + vertical_height,
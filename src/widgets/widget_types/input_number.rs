@@ -0,0 +1,256 @@
+use crate::widget_holder::{RenderInfo, UpdateInfo};
+use crate::widgets::widget_types::textbox::TextBox;
+use crate::Widget;
+use macroquad::input::MouseButton::Left;
+use macroquad::prelude::*;
+use std::any::Any;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A number type that `NumberInput` can edit.
+pub trait InputNumber: Copy + PartialOrd + Display + FromStr + 'static {
+    const MIN: Self;
+    const MAX: Self;
+    const DEFAULT_STEP: Self;
+
+    /// Whether `c` can be typed into the field.
+    fn allows(c: char) -> bool;
+
+    /// `self + by`, without overflowing.
+    fn step(self, by: Self) -> Self;
+
+    /// `self - by`, without overflowing.
+    fn step_back(self, by: Self) -> Self;
+
+    /// How the value is shown when it isn't being edited.
+    fn format(self) -> String {
+        self.to_string()
+    }
+}
+
+impl InputNumber for i64 {
+    const MIN: Self = i64::MIN;
+    const MAX: Self = i64::MAX;
+    const DEFAULT_STEP: Self = 1;
+
+    fn allows(c: char) -> bool {
+        c.is_ascii_digit() || c == '-'
+    }
+
+    fn step(self, by: Self) -> Self {
+        self.saturating_add(by)
+    }
+
+    fn step_back(self, by: Self) -> Self {
+        self.saturating_sub(by)
+    }
+}
+
+impl InputNumber for f64 {
+    const MIN: Self = f64::MIN;
+    const MAX: Self = f64::MAX;
+    const DEFAULT_STEP: Self = 0.1;
+
+    fn allows(c: char) -> bool {
+        c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')
+    }
+
+    fn step(self, by: Self) -> Self {
+        self + by
+    }
+
+    fn step_back(self, by: Self) -> Self {
+        self - by
+    }
+
+    /// Rounded to 6 decimals, so stepping by 0.1 doesn't show 0.30000000000000004.
+    fn format(self) -> String {
+        let text = format!("{self:.6}");
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+pub type InputInt = NumberInput<i64>;
+pub type InputFloat = NumberInput<f64>;
+
+/// A textbox that only takes numbers, with buttons to step the value down and up.
+///
+/// `value` follows the text as it's typed, kept between `min` and `max`. Text that isn't a
+/// number is put back to `value` when editing stops.
+pub struct NumberInput<T: InputNumber> {
+    pub value: T,
+    pub min: T,
+    pub max: T,
+    pub step: T,
+    /// Whether `value` changed this frame.
+    pub changed: bool,
+
+    textbox: TextBox,
+    /// Hovered and pressed, for the minus and plus buttons.
+    buttons: [(bool, bool); 2],
+}
+
+impl<T: InputNumber> NumberInput<T> {
+    pub fn new(value: T, label: Option<String>) -> Self {
+        let mut textbox = TextBox::new(value.format(), label);
+        textbox.set_filter(T::allows);
+
+        Self {
+            value,
+            min: T::MIN,
+            max: T::MAX,
+            step: T::DEFAULT_STEP,
+            changed: false,
+
+            textbox,
+            buttons: [(false, false); 2],
+        }
+    }
+
+    /// Clamps `value` to `min..=max`, now and whenever it changes.
+    pub fn set_range(&mut self, min: T, max: T) -> &mut Self {
+        self.min = min;
+        self.max = max;
+        self.value = self.clamped(self.value);
+        self
+    }
+
+    /// How much the buttons (and the arrow keys, when it's focused) change the value by.
+    pub fn set_step(&mut self, step: T) -> &mut Self {
+        self.step = step;
+        self
+    }
+
+    pub fn textbox(&mut self) -> &mut TextBox {
+        &mut self.textbox
+    }
+
+    fn clamped(&self, value: T) -> T {
+        if value < self.min {
+            self.min
+        } else if value > self.max {
+            self.max
+        } else {
+            value
+        }
+    }
+
+    fn set_value(&mut self, value: T) {
+        let value = self.clamped(value);
+        if value != self.value {
+            self.value = value;
+            self.changed = true;
+        }
+    }
+
+    /// Where the minus and plus buttons go, next to a textbox of `size`.
+    fn button_rects(rect: Rect, same_line: bool, size: Vec2) -> [Rect; 2] {
+        let y = match same_line {
+            true => rect.y,
+            _ => rect.y + rect.h,
+        };
+        let x = rect.x + size.x + 4.0;
+
+        [
+            Rect::new(x, y, size.y, size.y),
+            Rect::new(x + size.y + 2.0, y, size.y, size.y),
+        ]
+    }
+}
+
+impl<T: InputNumber> Widget for NumberInput<T> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut (dyn Any + 'static) {
+        self
+    }
+
+    fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
+        let size = self.textbox.render(info)?;
+        let font = info.font.into();
+        let rects = Self::button_rects(info.rect, info.same_line, size);
+
+        for ((rect, (hovered, pressed)), text) in rects.iter().zip(self.buttons).zip(["-", "+"]) {
+            info.draw_list.draw_rounded_rect(
+                rect.x,
+                rect.y,
+                rect.w,
+                rect.h,
+                info.theme.button_rounding,
+                match (hovered, pressed) {
+                    (true, false) => Color::from_vec(info.theme.button.to_vec() + vec4(0.13, 0.13, 0.13, 0.0)), // HOVER
+                    (_, true) => Color::from_vec(info.theme.button.to_vec() + vec4(0.25, 0.25, 0.25, 0.1)),     // PRESSED
+                    _ => info.theme.button,
+                },
+            );
+
+            let text_dim = info.theme.fonts.measure_text(text, font, info.theme.font_size, 1.0);
+            for _ in 0..4 {
+                info.draw_list.draw_text_ex(
+                    text,
+                    rect.x + (rect.w - text_dim.width) / 2.0,
+                    rect.y + (rect.h + text_dim.offset_y) / 2.0,
+                    TextParams {
+                        font,
+                        font_size: info.theme.font_size,
+                        color: info.theme.button_text,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        Some(vec2(rects[1].right() - info.rect.x, size.y))
+    }
+
+    fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
+        self.changed = false;
+
+        let was_editing = self.textbox.editing;
+        let size = self.textbox.update(info)?;
+
+        // Once editing stops, text that isn't a number goes back to the value, and the value is shown clamped
+        if self.textbox.editing || was_editing {
+            if let Ok(value) = self.textbox.value.parse() {
+                self.set_value(value);
+            }
+        }
+
+        let rects = Self::button_rects(info.rect, info.same_line, size);
+        let mut steps = [false; 2];
+
+        for (i, rect) in rects.iter().enumerate() {
+            let (hovered, pressed) = &mut self.buttons[i];
+            *hovered = rect.contains(info.mouse) && info.hover && !info.mouse_action.taken;
+
+            if *hovered && info.input.is_mouse_button_pressed(Left) {
+                *pressed = true;
+                info.request_focus = true;
+            }
+            if info.input.is_mouse_button_released(Left) {
+                steps[i] = *pressed && *hovered;
+                *pressed = false;
+            }
+        }
+
+        // Arrow keys step too, while the text isn't being edited
+        if info.focused && !self.textbox.editing {
+            steps[0] |= info.nav.left || info.nav.down;
+            steps[1] |= info.nav.right || info.nav.up;
+        }
+
+        if steps[0] {
+            self.set_value(self.value.step_back(self.step));
+        }
+        if steps[1] {
+            self.set_value(self.value.step(self.step));
+        }
+
+        if !self.textbox.editing {
+            self.textbox.value = self.value.format();
+        }
+
+        Some(vec2(rects[1].right() - info.rect.x, size.y))
+    }
+}
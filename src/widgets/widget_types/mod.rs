@@ -2,6 +2,7 @@ mod button;
 mod checkbox;
 mod dropdown;
 mod image_widget;
+mod input_number;
 mod progress_bar;
 mod separator;
 mod slider;
@@ -9,6 +10,8 @@ mod tab_holder;
 pub mod text;
 pub mod text_ex;
 mod textbox;
+mod text_edit;
+mod text_editor;
 mod radio_buttons;
 mod column;
 
@@ -17,10 +20,12 @@ pub use button::Button;
 pub use checkbox::Checkbox;
 pub use dropdown::Dropdown;
 pub use image_widget::ImageWidget;
+pub use input_number::{InputFloat, InputInt, InputNumber, NumberInput};
 pub use progress_bar::{ProgressBar, ProgressInfo};
 pub use separator::Separator;
 pub use slider::{Slider, SliderInfo};
 pub use tab_holder::TabHolder;
 pub use textbox::TextBox;
+pub use text_editor::TextEditor;
 pub use radio_buttons::RadioButtons;
 pub use column::Column;
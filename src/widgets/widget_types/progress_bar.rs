@@ -49,28 +49,28 @@ impl Widget for ProgressBar {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.text.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.text.to_string(),
                 0.0,
                 info.rect.y + text_dim.height + info.rect.h + 5.0,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
-                    color: WHITE,
+                    font_size: info.theme.font_size,
+                    color: info.theme.text,
                     ..Default::default()
                 },
             );
@@ -78,12 +78,13 @@ impl Widget for ProgressBar {
 
         // BASE BAR
         let r_width = info.win_rect.w - text_dim.width - 15.0;
-        draw_rectangle(
+        info.draw_list.draw_rounded_rect(
             text_dim.width + 5.0,
             info.rect.y + info.rect.h + 5.0,
             r_width,
             text_dim.height + 4.0,
-            Color::new(0.1, 0.25, 0.4, 1.0),
+            info.theme.frame_rounding,
+            info.theme.progress_bar,
         );
 
         // VALUE
@@ -92,18 +93,19 @@ impl Widget for ProgressBar {
             ProgressInfo::Int { max, .. } => ("int", max as f64),
         };
 
-        draw_rectangle(
+        info.draw_list.draw_rounded_rect(
             text_dim.width + 5.0,
             info.rect.y + info.rect.h + 5.0,
             r_width * (self.value / max) as f32,
             text_dim.height + 4.0,
-            Color::new(0.34, 0.54, 0.8, 1.0),
+            info.theme.frame_rounding,
+            info.theme.progress_fill,
         );
 
         //PRINT VALUE
 
         for _ in 0..14 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &match slider_type {
                     "float" => format!("{:.2}", self.value),
                     "int" => (self.value as i32).to_string(),
@@ -113,11 +115,11 @@ impl Widget for ProgressBar {
                 info.rect.y + text_dim.height + info.rect.h + 5.0,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
-                    color: WHITE,
+                    font_size: info.theme.font_size,
+                    color: info.theme.text,
                     ..Default::default()
                 },
             );
@@ -127,13 +129,13 @@ impl Widget for ProgressBar {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.text.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
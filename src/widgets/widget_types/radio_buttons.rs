@@ -43,13 +43,13 @@ impl Widget for RadioButtons {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let char_dim = measure_text(
+        let char_dim = info.theme.fonts.measure_text(
             "A",
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
@@ -62,13 +62,13 @@ impl Widget for RadioButtons {
 
         for i in 0..self.options.len() {
             let text = &self.options[i];
-            let text_dim = measure_text(
+            let text_dim = info.theme.fonts.measure_text(
                 text,
                 match &info.font {
-                    Some(f) => Some(&f),
+                    Some(f) => Some(f),
                     _ => None,
                 },
-                14,
+                info.theme.font_size,
                 1.0,
             );
 
@@ -86,43 +86,42 @@ impl Widget for RadioButtons {
 
             // DRAW CIRCLE
 
-            draw_circle(
+            info.draw_list.draw_circle(
                 info.rect.x + 10.0,
                 info.rect.y + vertical_height - 8.0 + text_y - self.padding,
                 8.0,
                 match (hovered, pressed) {
-                    (true, false) => Color::new(0.22, 0.35, 0.55, 0.9),
-                    (_, true) => Color::new(0.2, 0.3, 0.5, 0.7),
-                    _ => Color::new(0.2, 0.3, 0.6, 0.8),
+                    (true, false) => info.theme.radio_hover,
+                    (_, true) => info.theme.radio_press,
+                    _ => info.theme.radio,
                 },
             );
 
             if self.value == *text {
-                draw_circle(
+                info.draw_list.draw_circle(
                     info.rect.x + 10.0,
                     info.rect.y + vertical_height - 8.0 + text_y - self.padding,
                     5.0,
-                    Color::new(0.3, 0.6, 0.9, 1.0),
+                    info.theme.radio_dot,
                 );
             }
 
             // DRAW TEXT
 
             for _ in 0..4 {
-                draw_text_ex(
+                info.draw_list.draw_text_ex(
                     text,
                     info.rect.x + text_dim.height + 10.0,
                     info.rect.y + vertical_height + text_y - self.padding - 3.0,
                     TextParams {
                         font: match &info.font {
-                            Some(f) => Some(&f),
+                            Some(f) => Some(f),
                             _ => None,
                         },
-                        font_size: 14,
-                        color: match (hovered, pressed) {
-                            (true, false) => WHITE,
-                            (_, true) => WHITE.with_alpha(0.75),
-                            _ => WHITE.with_alpha(0.82),
+                        font_size: info.theme.font_size,
+                        color: match hovered && !pressed {
+                            true => info.theme.text,
+                            _ => info.theme.text_muted,
                         },
                         ..Default::default()
                     },
@@ -134,13 +133,13 @@ impl Widget for RadioButtons {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let char_dim = measure_text(
+        let char_dim = info.theme.fonts.measure_text(
             "A",
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
@@ -153,21 +152,22 @@ impl Widget for RadioButtons {
 
         self.hovered = None;
         self.clicked = None;
+        let mut focus_rect: Option<Rect> = None;
 
         for i in 0..self.options.len() {
             let text = &self.options[i];
-            let mut text_dim = measure_text(
+            let mut text_dim = info.theme.fonts.measure_text(
                 text,
                 match &info.font {
-                    Some(f) => Some(&f),
+                    Some(f) => Some(f),
                     _ => None,
                 },
-                14,
+                info.theme.font_size,
                 1.0,
             );
 
             text_y += text_dim.height + self.padding;
-            if text.len() > 0 {
+            if !text.is_empty() {
                 text_dim.width += 5.0;
             }
             if text_dim.width > text_width {
@@ -181,15 +181,21 @@ impl Widget for RadioButtons {
                 char_dim.height + 10.0,
             );
 
+            focus_rect = Some(match focus_rect {
+                Some(r) => r.combine_with(rect),
+                _ => rect,
+            });
+
             if rect.contains(info.mouse) && info.hover && !info.mouse_action.taken {
                 self.hovered = Some(i);
-                if is_mouse_button_pressed(MouseButton::Left) {
+                if info.input.is_mouse_button_pressed(MouseButton::Left) {
                     self.pressed = Some(i);
+                    info.request_focus = true;
                 }
             }
         }
 
-        if is_mouse_button_released(MouseButton::Left) {
+        if info.input.is_mouse_button_released(MouseButton::Left) {
             if self.pressed.is_some() && self.hovered.is_some() {
                 self.clicked = self.pressed;
                 self.value = self.options[self.clicked.unwrap()].clone();
@@ -197,6 +203,36 @@ impl Widget for RadioButtons {
             self.pressed = None;
         }
 
+        // Arrow keys pick the previous/next option, activating (e.g. gamepad A) cycles through them
+        if !self.options.is_empty() {
+            let current = self.options.iter().position(|x| *x == self.value);
+            let last = self.options.len() - 1;
+
+            let selected = match current {
+                Some(i) if info.nav.activate => Some((i + 1) % self.options.len()),
+                Some(i) if info.nav.up || info.nav.left => Some(i.saturating_sub(1)),
+                Some(i) if info.nav.down || info.nav.right => Some((i + 1).min(last)),
+                None if info.nav.activate
+                    || info.nav.up
+                    || info.nav.left
+                    || info.nav.down
+                    || info.nav.right =>
+                {
+                    Some(0)
+                }
+                _ => None,
+            };
+
+            if let Some(i) = selected {
+                if current != Some(i) {
+                    self.clicked = Some(i);
+                    self.value = self.options[i].clone();
+                }
+            }
+        }
+
+        info.focus_rect = focus_rect;
+
         Some(vec2(text_width + char_dim.height + 10.0, text_y - self.padding))
     }
 }
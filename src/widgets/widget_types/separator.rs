@@ -6,7 +6,14 @@ use std::any::Any;
 pub struct Separator {
     pub thickness: f32,
     pub padding: f32,
-    pub color: Color,
+    /// Uses the theme's `separator` color when `None`.
+    pub color: Option<Color>,
+}
+
+impl Default for Separator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Separator {
@@ -14,7 +21,7 @@ impl Separator {
         Self {
             thickness: 2.0,
             padding: 5.0,
-            color: Color::new(1.0, 1.0, 1.0, 0.65),
+            color: None,
         }
     }
 
@@ -29,7 +36,7 @@ impl Separator {
     }
 
     pub fn set_color(&mut self, color: Color) -> &mut Self {
-        self.color = color;
+        self.color = Some(color);
         self
     }
 }
@@ -44,24 +51,24 @@ impl Widget for Separator {
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
         if info.same_line {
-            draw_line(
+            info.draw_list.draw_line(
                 info.rect.x + self.padding + (self.thickness / 4.0).floor(),
                 info.rect.y,
                 info.rect.x + self.padding + (self.thickness / 4.0).floor(),
                 info.rect.y + 10.0 + info.rect.h,
                 self.thickness,
-                self.color,
+                self.color.unwrap_or(info.theme.separator),
             );
             
             Some(vec2(self.padding * 2.0, 0.0))
         } else {
-            draw_line(
+            info.draw_list.draw_line(
                 5.0,
                 info.rect.y + info.rect.h + self.padding + (self.thickness / 4.0).floor(),
                 info.win_rect.w - 15.0,
                 info.rect.y + info.rect.h + self.padding + (self.thickness / 4.0).floor(),
                 self.thickness,
-                self.color,
+                self.color.unwrap_or(info.theme.separator),
             );
             
             Some(vec2(info.rect.w, self.padding * 2.0))
@@ -1,3 +1,4 @@
+use crate::ui::focus::NavDirection;
 use crate::widgets::widget::Widget;
 use crate::widgets::widget_holder::{RenderInfo, UpdateInfo};
 use macroquad::input::MouseButton::Left;
@@ -52,28 +53,28 @@ impl Widget for Slider {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.text.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.text.to_string(),
                 info.rect.x,
                 info.rect.y + text_dim.height + info.rect.h + 5.0,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
-                    color: WHITE,
+                    font_size: info.theme.font_size,
+                    color: info.theme.text,
                     ..Default::default()
                 },
             );
@@ -81,15 +82,16 @@ impl Widget for Slider {
 
         // BASE BAR
         let r_width = info.win_rect.w - text_dim.width - 15.0;
-        draw_rectangle(
+        info.draw_list.draw_rounded_rect(
             info.rect.x + text_dim.width + 5.0,
             info.rect.y + info.rect.h + 5.0,
             r_width,
             text_dim.height + 4.0,
+            info.theme.frame_rounding,
             match (self.hovered, self.pressed) {
-                (true, false) => Color::new(0.15, 0.3, 0.5, 1.0),
-                (_, true) => Color::new(0.2, 0.35, 0.55, 1.0),
-                _ => Color::new(0.1, 0.25, 0.4, 1.0),
+                (true, false) => info.theme.slider_hover,
+                (_, true) => info.theme.slider_press,
+                _ => info.theme.slider,
             },
         );
 
@@ -99,19 +101,20 @@ impl Widget for Slider {
             SliderInfo::Int { min, max, .. } => ("int", min as f64, max as f64),
         };
 
-        draw_rectangle(
+        info.draw_list.draw_rounded_rect(
             info.rect.x + text_dim.width
                 + 5.0
                 + (((self.value - min) / (max - min)) as f32) * (r_width - self.value_thickness),
             info.rect.y + info.rect.h + 5.0,
             self.value_thickness,
             text_dim.height + 4.0,
-            Color::new(0.34, 0.54, 0.8, 1.0),
+            info.theme.frame_rounding,
+            info.theme.slider_grab,
         );
 
         //PRINT VALUE
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &match slider_type {
                     "float" => format!("{:.2}", self.value),
                     "int" => (self.value as i32).to_string(),
@@ -121,11 +124,11 @@ impl Widget for Slider {
                 info.rect.y + text_dim.height + info.rect.h + 5.0,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
-                    color: WHITE,
+                    font_size: info.theme.font_size,
+                    color: info.theme.text,
                     ..Default::default()
                 },
             );
@@ -135,13 +138,13 @@ impl Widget for Slider {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.text.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
@@ -156,14 +159,15 @@ impl Widget for Slider {
 
         if rect.contains(info.mouse) && !info.mouse_action.taken {
             self.hovered = true;
-            if is_mouse_button_pressed(Left) {
+            if info.input.is_mouse_button_pressed(Left) {
                 self.pressed = true;
+                info.request_focus = true;
             }
         } else {
             self.hovered = false;
         }
 
-        if is_mouse_button_released(Left) {
+        if info.input.is_mouse_button_released(Left) {
             self.pressed = false;
         }
 
@@ -180,11 +184,31 @@ impl Widget for Slider {
                 + min;
         }
 
+        // Arrow keys (and D-pad left/right) step by one for int sliders and by a hundredth of the range for float sliders
+        let step = match slider_type {
+            "int" => 1.0,
+            _ => (max - min) / 100.0,
+        };
+        let pad_left = info.nav.move_focus == Some(NavDirection::Left);
+        let pad_right = info.nav.move_focus == Some(NavDirection::Right);
+        if pad_left || pad_right {
+            info.focus.nav_used = true;
+        }
+
+        if info.nav.right || info.nav.up || pad_right {
+            self.value += step;
+        }
+        if info.nav.left || info.nav.down || pad_left {
+            self.value -= step;
+        }
+
         self.value = self.value.clamp(min, max);
         if slider_type == "int" {
             self.value = self.value.round()
         }
 
+        info.focus_rect = Some(rect);
+
         Some(vec2(text_dim.width, text_dim.height + 10.0))
     }
 }
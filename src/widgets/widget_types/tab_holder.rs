@@ -43,12 +43,12 @@ impl Widget for TabHolder {
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
         // DRAW BASE
-        draw_rectangle(
+        info.draw_list.draw_rectangle(
             -5.0,
             info.rect.y + info.rect.h,
             info.win_rect.w - 5.0,
             30.0,
-            BLACK,
+            info.theme.tab_bar,
         );
 
         // DRAW UNDERLINES
@@ -56,76 +56,76 @@ impl Widget for TabHolder {
 
         for i in 0..length {
             if self.tab_pressed == i as i16 {
-                draw_rectangle(
+                info.draw_list.draw_rectangle(
                     i as f32 * info.win_rect.w / length as f32 - 4.0,
                     info.rect.y + info.rect.h,
                     info.win_rect.w / length as f32,
                     30.0,
-                    Color::new(0.2, 0.5, 0.9, 0.2),
+                    info.theme.tab_press,
                 );
-                draw_rectangle_lines(
+                info.draw_list.draw_rectangle_lines(
                     i as f32 * info.win_rect.w / length as f32 - 4.0,
                     info.rect.y + info.rect.h,
                     info.win_rect.w / length as f32,
                     30.0,
                     2.0,
-                    Color::new(1., 1., 1., 0.5),
+                    info.theme.tab_press_stroke,
                 );
             }
             if self.value == i {
-                draw_rectangle(
+                info.draw_list.draw_rectangle(
                     i as f32 * info.win_rect.w / length as f32 - 4.0,
                     info.rect.y + info.rect.h,
                     info.win_rect.w / length as f32,
                     30.0,
-                    Color::new(0.2, 0.4, 0.7, 0.2),
+                    info.theme.tab_active,
                 );
-                draw_rectangle_lines(
+                info.draw_list.draw_rectangle_lines(
                     i as f32 * info.win_rect.w / length as f32 - 4.0,
                     info.rect.y + info.rect.h,
                     info.win_rect.w / length as f32,
                     30.0,
                     2.0,
-                    Color::new(0.2, 0.5, 0.7, 0.3),
+                    info.theme.tab_active_stroke,
                 );
-                draw_rectangle(
+                info.draw_list.draw_rectangle(
                     i as f32 * info.win_rect.w / length as f32 - 2.0,
                     info.rect.y + info.rect.h + 25.0,
                     info.win_rect.w / length as f32 - 4.0,
                     5.0,
-                    Color::new(0.2, 0.5, 0.8, 1.0),
+                    info.theme.tab_underline,
                 );
             }
             if self.hovered == i as i16 {
-                draw_rectangle(
+                info.draw_list.draw_rectangle(
                     i as f32 * info.win_rect.w / length as f32 - 4.0,
                     info.rect.y + info.rect.h,
                     info.win_rect.w / length as f32,
                     30.0,
-                    Color::new(1.0, 1.0, 1.0, 0.1),
+                    info.theme.tab_hover,
                 );
-                draw_rectangle_lines(
+                info.draw_list.draw_rectangle_lines(
                     i as f32 * info.win_rect.w / length as f32 - 4.0,
                     info.rect.y + info.rect.h,
                     info.win_rect.w / length as f32,
                     30.0,
                     2.0,
-                    Color::new(1.0, 1.0, 1.0, 0.3),
+                    info.theme.tab_hover_stroke,
                 );
             }
 
-            let text_dim = measure_text(
+            let text_dim = info.theme.fonts.measure_text(
                 &self.tabs[i],
                 match &info.font {
-                    Some(f) => Some(&f),
+                    Some(f) => Some(f),
                     _ => None,
                 },
-                13,
+                info.theme.font_size.saturating_sub(1),
                 1.0,
             );
 
             for _ in 0..4 {
-                draw_text_ex(
+                info.draw_list.draw_text_ex(
                     &self.tabs[i],
                     i as f32 * info.win_rect.w / length as f32
                         + info.win_rect.w / length as f32 / 2.0
@@ -134,10 +134,11 @@ impl Widget for TabHolder {
                     info.rect.y + info.rect.h + 18.0,
                     TextParams {
                         font: match &info.font {
-                            Some(f) => Some(&f),
+                            Some(f) => Some(f),
                             _ => None,
                         },
-                        font_size: 13,
+                        font_size: info.theme.font_size.saturating_sub(1),
+                        color: info.theme.text,
                         ..Default::default()
                     },
                 );
@@ -170,19 +171,34 @@ impl Widget for TabHolder {
 
             if rect.contains(info.mouse) && !info.mouse_action.taken {
                 self.hovered = i as i16;
-                if (self.holdable && self.pressed) || is_mouse_button_pressed(Left) {
+                if (self.holdable && self.pressed) || info.input.is_mouse_button_pressed(Left) {
                     self.value = i;
                     self.tab_pressed = i as i16;
                 }
             }
         }
 
-        if holder_rect.contains(info.mouse) && is_mouse_button_pressed(Left) {
+        if holder_rect.contains(info.mouse) && info.input.is_mouse_button_pressed(Left) {
             self.pressed = true;
-        } else if is_mouse_button_released(Left) {
+            info.request_focus = true;
+        } else if info.input.is_mouse_button_released(Left) {
             self.pressed = false;
         }
 
+        // Left/Right (or the shoulder buttons) switch tabs
+        if length > 0 {
+            if (info.nav.left || info.nav.tab_prev) && self.value > 0 {
+                self.value -= 1;
+                self.tab_pressed = self.value as i16;
+            }
+            if (info.nav.right || info.nav.tab_next) && self.value + 1 < length {
+                self.value += 1;
+                self.tab_pressed = self.value as i16;
+            }
+        }
+
+        info.focus_rect = Some(holder_rect);
+
         Some(vec2(info.win_rect.w, 30.0))
     }
 }
@@ -22,13 +22,13 @@ impl Widget for Text {
     }
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.value.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
         
@@ -38,17 +38,17 @@ impl Widget for Text {
         };
 
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.value.to_string(),
                 info.rect.x,
                 info.rect.y + text_dim.height + vertical_height,
                 TextParams {
                     font: match &info.font {
-                        Some(f) => Some(&f),
+                        Some(f) => Some(f),
                         _ => None,
                     },
-                    font_size: 14,
-                    color: WHITE,
+                    font_size: info.theme.font_size,
+                    color: info.theme.text,
                     ..Default::default()
                 },
             );
@@ -58,13 +58,13 @@ impl Widget for Text {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let text_dim = measure_text(
+        let text_dim = info.theme.fonts.measure_text(
             &self.value.to_string(),
             match &info.font {
-                Some(f) => Some(&f),
+                Some(f) => Some(f),
                 _ => None,
             },
-            14,
+            info.theme.font_size,
             1.0,
         );
 
@@ -0,0 +1,322 @@
+use crate::Error;
+use arboard::Clipboard;
+
+/// The closest char boundary at or before `i`, so a caret set from outside can't split a character.
+pub(crate) fn floor_char_boundary(s: &str, i: usize) -> usize {
+    let mut i = i.min(s.len());
+    while !s.is_char_boundary(i) {
+        i -= 1;
+    }
+    i
+}
+
+/// The char boundary before `i`.
+pub(crate) fn prev_char(s: &str, i: usize) -> usize {
+    s[..i].chars().next_back().map_or(0, |c| i - c.len_utf8())
+}
+
+/// The char boundary after `i`.
+pub(crate) fn next_char(s: &str, i: usize) -> usize {
+    s[i..].chars().next().map_or(i, |c| i + c.len_utf8())
+}
+
+/// Clicks closer together than this many seconds count as a double or triple click.
+pub(crate) const MULTI_CLICK_TIME: f64 = 0.3;
+
+/// Words, spaces and everything else, for moving and selecting a word at a time.
+#[derive(PartialEq)]
+enum CharClass {
+    Word,
+    Space,
+    Other,
+}
+
+fn char_class(c: char) -> CharClass {
+    if c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else if c.is_whitespace() {
+        CharClass::Space
+    } else {
+        CharClass::Other
+    }
+}
+
+/// The start of the word before `i`, skipping the spaces in between.
+pub(crate) fn prev_word(s: &str, i: usize) -> usize {
+    let before = s[..i].trim_end();
+    let Some(last) = before.chars().next_back() else {
+        return 0;
+    };
+
+    let class = char_class(last);
+    before.trim_end_matches(|c| char_class(c) == class).len()
+}
+
+/// The end of the word after `i`, skipping the spaces in between.
+pub(crate) fn next_word(s: &str, i: usize) -> usize {
+    let after = s[i..].trim_start();
+    let Some(first) = after.chars().next() else {
+        return s.len();
+    };
+
+    let class = char_class(first);
+    s.len() - after.trim_start_matches(|c| char_class(c) == class).len()
+}
+
+/// The word around `i` (or the run of spaces or punctuation), for double-click selection.
+pub(crate) fn word_at(s: &str, i: usize) -> (usize, usize) {
+    let Some(c) = s[i..].chars().next().or_else(|| s[..i].chars().next_back()) else {
+        return (i, i);
+    };
+
+    let class = char_class(c);
+    let start = s[..i].trim_end_matches(|c| char_class(c) == class).len();
+    let end = s.len() - s[i..].trim_start_matches(|c| char_class(c) == class).len();
+    (start, end)
+}
+
+/// A text widget's clipboard, opened the first time copy or paste is used and kept open after,
+/// since on some platforms copied text only stays on the clipboard while it's open.
+///
+/// Without a clipboard, copy and paste just do nothing, and `error` says why.
+#[derive(Default)]
+pub(crate) struct TextClipboard {
+    clipboard: Option<Clipboard>,
+    pub error: Option<Error>,
+}
+
+impl TextClipboard {
+    fn open(&mut self) -> Option<&mut Clipboard> {
+        if self.clipboard.is_none() {
+            match Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(e) => self.error = Some(Error::Clipboard(e.to_string())),
+            }
+        }
+
+        self.clipboard.as_mut()
+    }
+
+    pub fn get_text(&mut self) -> Option<String> {
+        let result = self.open()?.get_text();
+        self.check(result)
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        if let Some(clipboard) = self.open() {
+            let result = clipboard.set_text(text);
+            self.check(result);
+        }
+    }
+
+    fn check<T>(&mut self, result: Result<T, arboard::Error>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.error = None;
+                Some(value)
+            }
+            // An empty clipboard isn't a problem with the clipboard
+            Err(arboard::Error::ContentNotAvailable) => None,
+            Err(e) => {
+                self.error = Some(Error::Clipboard(e.to_string()));
+                None
+            }
+        }
+    }
+}
+
+/// A text field's contents, caret and selection, to go back to with undo.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct TextState {
+    pub value: String,
+    pub caret: usize,
+    /// Where the selection started and ended, in the order the widget keeps them.
+    pub selection: Option<(usize, usize)>,
+}
+
+/// What an edit did, so a run of the same kind can be undone in one go.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum EditKind {
+    Typing,
+    Deleting,
+    /// Pastes, cuts and anything else that's never grouped.
+    Other,
+}
+
+/// Edits more than this many seconds apart aren't grouped.
+const GROUP_TIME: f64 = 1.0;
+
+/// Undo and redo for a text field, keeping at most `depth` undo steps.
+pub(crate) struct UndoStack {
+    undo: Vec<TextState>,
+    redo: Vec<TextState>,
+    depth: usize,
+
+    /// The kind, time and resulting caret of the last edit, to tell whether the next one continues it.
+    last_edit: Option<(EditKind, f64, usize)>,
+}
+
+impl UndoStack {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo: vec![],
+            redo: vec![],
+            depth,
+            last_edit: None,
+        }
+    }
+
+    /// Records an edit that changed `before`. Typing or deleting where the last edit of the same kind
+    /// left off, soon after it, joins that edit's undo step.
+    pub fn record(&mut self, before: TextState, kind: EditKind, caret_after: usize, time: f64) {
+        let grouped = match self.last_edit {
+            Some((last_kind, last_time, last_caret)) => {
+                kind != EditKind::Other
+                    && kind == last_kind
+                    && before.caret == last_caret
+                    && time - last_time < GROUP_TIME
+            }
+            _ => false,
+        };
+
+        if !grouped {
+            self.push_undo(before);
+        }
+
+        self.redo.clear();
+        self.last_edit = Some((kind, time, caret_after));
+    }
+
+    /// The state to go back to, if there is one. `current` becomes the redo step.
+    pub fn undo(&mut self, current: TextState) -> Option<TextState> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        self.last_edit = None;
+        Some(state)
+    }
+
+    /// The state an undo went back from, if nothing was edited since.
+    pub fn redo(&mut self, current: TextState) -> Option<TextState> {
+        let state = self.redo.pop()?;
+        self.push_undo(current);
+        self.last_edit = None;
+        Some(state)
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.trim();
+    }
+
+    fn push_undo(&mut self, state: TextState) {
+        self.undo.push(state);
+        self.trim();
+    }
+
+    /// Drops the oldest undo steps past `depth`.
+    fn trim(&mut self) {
+        if self.undo.len() > self.depth {
+            self.undo.drain(..self.undo.len() - self.depth);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(value: &str) -> TextState {
+        TextState {
+            value: value.to_string(),
+            caret: value.chars().count(),
+            selection: None,
+        }
+    }
+
+    /// Types `text` onto `value` one character at a time, `step` seconds apart.
+    fn type_text(stack: &mut UndoStack, value: &mut String, text: &str, time: &mut f64, step: f64) {
+        for c in text.chars() {
+            let before = state(value);
+            value.push(c);
+            stack.record(before, EditKind::Typing, value.chars().count(), *time);
+            *time += step;
+        }
+    }
+
+    #[test]
+    fn quick_typing_is_one_undo_step() {
+        let mut stack = UndoStack::new(10);
+        let (mut value, mut time) = (String::new(), 0.0);
+        type_text(&mut stack, &mut value, "hello", &mut time, 0.1);
+
+        assert_eq!(stack.undo(state(&value)), Some(state("")));
+        assert_eq!(stack.undo(state("")), None);
+    }
+
+    #[test]
+    fn pauses_and_other_edits_start_a_new_step() {
+        let mut stack = UndoStack::new(10);
+        let (mut value, mut time) = (String::new(), 0.0);
+        type_text(&mut stack, &mut value, "ab", &mut time, 0.1);
+        time += GROUP_TIME;
+        type_text(&mut stack, &mut value, "cd", &mut time, 0.1);
+
+        let before = state(&value);
+        value.pop();
+        stack.record(before, EditKind::Deleting, value.chars().count(), time);
+
+        assert_eq!(stack.undo(state(&value)), Some(state("abcd")));
+        assert_eq!(stack.undo(state("abcd")), Some(state("ab")));
+        assert_eq!(stack.undo(state("ab")), Some(state("")));
+    }
+
+    #[test]
+    fn typing_somewhere_else_starts_a_new_step() {
+        let mut stack = UndoStack::new(10);
+        stack.record(state(""), EditKind::Typing, 1, 0.0);
+
+        let mut moved = state("a");
+        moved.caret = 0;
+        stack.record(moved.clone(), EditKind::Typing, 1, 0.1);
+
+        assert_eq!(stack.undo(state("ba")), Some(moved));
+    }
+
+    #[test]
+    fn redo_goes_back_until_the_next_edit() {
+        let mut stack = UndoStack::new(10);
+        stack.record(state(""), EditKind::Other, 1, 0.0);
+        stack.record(state("a"), EditKind::Other, 2, 0.1);
+
+        assert_eq!(stack.undo(state("ab")), Some(state("a")));
+        assert_eq!(stack.undo(state("a")), Some(state("")));
+        assert_eq!(stack.redo(state("")), Some(state("a")));
+        assert_eq!(stack.redo(state("a")), Some(state("ab")));
+        assert_eq!(stack.redo(state("ab")), None);
+
+        stack.undo(state("ab"));
+        stack.record(state("a"), EditKind::Other, 2, 0.2);
+        assert_eq!(stack.redo(state("ac")), None);
+    }
+
+    #[test]
+    fn redo_keeps_to_the_depth() {
+        let mut stack = UndoStack::new(5);
+        for (i, value) in ["", "a", "ab", "abc", "abcd"].into_iter().enumerate() {
+            stack.record(state(value), EditKind::Other, value.len() + 1, i as f64);
+        }
+        for value in ["abcde", "abcd", "abc"] {
+            stack.undo(state(value));
+        }
+
+        stack.set_depth(2);
+        for value in ["ab", "abc", "abcd"] {
+            stack.redo(state(value));
+        }
+
+        assert_eq!(stack.undo.len(), 2);
+        assert_eq!(stack.undo(state("abcde")), Some(state("abcd")));
+        assert_eq!(stack.undo(state("abcd")), Some(state("abc")));
+        assert_eq!(stack.undo(state("abc")), None);
+    }
+}
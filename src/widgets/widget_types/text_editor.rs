@@ -0,0 +1,697 @@
+use crate::ui::fonts::FontSet;
+use crate::ui::input::UiInput;
+use crate::widget_holder::{RenderInfo, UpdateInfo};
+use crate::widgets::widget_types::text_edit::{
+    floor_char_boundary, next_char, next_word, prev_char, prev_word, word_at, EditKind,
+    TextClipboard, TextState, UndoStack, MULTI_CLICK_TIME,
+};
+use crate::{Error, FontStyle, Widget, WindowTheme};
+use macroquad::input::MouseButton::Left;
+use macroquad::miniquad::CursorIcon;
+use macroquad::prelude::*;
+use std::any::Any;
+
+const PADDING: f32 = 5.0;
+
+/// A multi-line text box, drawn with the theme's monospace font.
+///
+/// `caret` is a byte offset into `value`, always on a char boundary. The selection runs from
+/// where it was started to the caret.
+pub struct TextEditor {
+    pub value: String,
+    pub caret: usize,
+    pub word_wrap: bool,
+    pub size: Vec2,
+    pub editing: bool,
+
+    anchor: Option<usize>,
+    scroll: Vec2,
+    /// The x the caret keeps when moving up and down, so it doesn't drift left over short lines.
+    target_x: Option<f32>,
+    hovered: bool,
+    dragging: bool,
+    /// When the last click was, and how many came before it in quick succession.
+    last_click: Option<f64>,
+    clicks: u32,
+    clipboard: TextClipboard,
+
+    // repeat state
+    last_keycode: Option<KeyCode>,
+    key_repeat_timer: f32,
+    repeat_delay: f32,
+    repeat_interval: f32,
+
+    history: UndoStack,
+    /// `value` as the caller last saw it, to tell their changes from the user's.
+    synced: String,
+    /// The lines from the last `layout`, and the text, width, word wrap and font size they were laid out with.
+    lines: Vec<(usize, usize)>,
+    laid_out: Option<(String, f32, bool, u16)>,
+}
+
+impl TextEditor {
+    pub fn new(default_text: String) -> Self {
+        Self {
+            synced: default_text.clone(),
+            value: default_text,
+            caret: 0,
+            word_wrap: true,
+            size: vec2(300.0, 150.0),
+            editing: false,
+
+            anchor: None,
+            scroll: Vec2::ZERO,
+            target_x: None,
+            hovered: false,
+            dragging: false,
+            last_click: None,
+            clicks: 0,
+            clipboard: TextClipboard::default(),
+
+            last_keycode: None,
+            key_repeat_timer: 0.0,
+            repeat_delay: 0.4,
+            repeat_interval: 0.03,
+
+            history: UndoStack::new(100),
+            lines: vec![],
+            laid_out: None,
+        }
+    }
+
+    pub fn set_word_wrap(&mut self, word_wrap: bool) -> &mut Self {
+        self.word_wrap = word_wrap;
+        self
+    }
+
+    pub fn set_size(&mut self, size: Vec2) -> &mut Self {
+        self.size = size;
+        self
+    }
+
+    /// How many steps Ctrl+Z can go back. Defaults to 100.
+    pub fn set_undo_depth(&mut self, depth: usize) -> &mut Self {
+        self.history.set_depth(depth);
+        self
+    }
+
+    /// Why the clipboard couldn't be used the last time copy or paste was, if it couldn't. They do nothing until it can.
+    pub fn clipboard_error(&self) -> Option<&Error> {
+        self.clipboard.error.as_ref()
+    }
+
+    /// The selected byte range, if anything is selected.
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        match self.anchor {
+            Some(anchor) if anchor != self.caret => {
+                Some((anchor.min(self.caret), anchor.max(self.caret)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Hands the user's edits from the last update to `text`, or takes `text` if the caller changed it instead.
+    pub(crate) fn sync(&mut self, text: &mut String) {
+        if self.value != self.synced {
+            text.clone_from(&self.value);
+        } else if *text != self.value {
+            self.value.clone_from(text);
+            self.caret = floor_char_boundary(&self.value, self.caret);
+            self.anchor = None;
+        }
+
+        self.synced.clone_from(&self.value);
+    }
+
+    fn state(&self) -> TextState {
+        TextState {
+            value: self.value.clone(),
+            caret: self.caret,
+            selection: self.anchor.map(|anchor| (anchor, self.caret)),
+        }
+    }
+
+    fn restore(&mut self, state: TextState) {
+        self.value = state.value;
+        self.caret = state.caret;
+        self.anchor = state.selection.map(|(anchor, _)| anchor);
+    }
+
+    fn line_height(theme: &WindowTheme) -> f32 {
+        theme.font_size as f32 + 4.0
+    }
+
+    /// Splits `value` into the lines it's drawn as, as byte ranges without the `\n`.
+    /// With word wrap, lines break after the last space that fits, or mid-word if there isn't one.
+    fn layout(&self, fonts: &FontSet, font: Option<&Font>, font_size: u16) -> Vec<(usize, usize)> {
+        let max_width = self.size.x - PADDING * 2.0;
+        let mut lines = vec![];
+        let mut start = 0;
+
+        for (end, _) in self.value.match_indices('\n').chain([(self.value.len(), "")]) {
+            if !self.word_wrap {
+                lines.push((start, end));
+                start = end + 1;
+                continue;
+            }
+
+            let mut width = 0.0;
+            let mut last_space = None;
+
+            for (i, c) in self.value[start..end].char_indices() {
+                let i = start + i;
+                let w = fonts.measure_text(&self.value[i..i + c.len_utf8()], font, font_size, 1.0).width;
+
+                if width + w > max_width && i > start {
+                    let wrap_at = match last_space {
+                        Some(s) if s > start => s,
+                        _ => i,
+                    };
+                    lines.push((start, wrap_at));
+                    width = fonts.measure_text(&self.value[wrap_at..i], font, font_size, 1.0).width;
+                    start = wrap_at;
+                    last_space = None;
+                }
+
+                width += w;
+                if c.is_whitespace() {
+                    last_space = Some(i + c.len_utf8());
+                }
+            }
+
+            lines.push((start, end));
+            start = end + 1;
+        }
+
+        lines
+    }
+
+    /// Lays `value` out again if it, the width, word wrap or font size changed since the last time.
+    fn relayout(&mut self, fonts: &FontSet, font: Option<&Font>, font_size: u16) {
+        if let Some((text, width, word_wrap, size)) = &self.laid_out {
+            if *text == self.value && *width == self.size.x && *word_wrap == self.word_wrap && *size == font_size {
+                return;
+            }
+        }
+
+        self.lines = self.layout(fonts, font, font_size);
+        self.laid_out = Some((self.value.clone(), self.size.x, self.word_wrap, font_size));
+    }
+
+    /// The line the caret is drawn on. At a wrap it's the start of the next line.
+    fn line_of(lines: &[(usize, usize)], i: usize) -> usize {
+        lines.iter().rposition(|l| l.0 <= i).unwrap_or(0)
+    }
+
+    /// Where the caret goes for the end of line `l`. A wrapped line's end is the next line's start,
+    /// so this stops before the character it wrapped after.
+    fn line_end(&self, lines: &[(usize, usize)], l: usize) -> usize {
+        let (start, end) = lines[l];
+        match lines.get(l + 1) {
+            Some(next) if next.0 == end && end > start => prev_char(&self.value, end),
+            _ => end,
+        }
+    }
+
+    /// The char boundary on line `l` closest to `x`.
+    fn hit_line(&self, lines: &[(usize, usize)], l: usize, x: f32, fonts: &FontSet, font: Option<&Font>, font_size: u16) -> usize {
+        let (start, _) = lines[l];
+        let end = self.line_end(lines, l);
+        let mut width = 0.0;
+
+        for (i, c) in self.value[start..end].char_indices() {
+            let i = start + i;
+            let w = fonts.measure_text(&self.value[i..i + c.len_utf8()], font, font_size, 1.0).width;
+            if x < width + w / 2.0 {
+                return i;
+            }
+            width += w;
+        }
+
+        end
+    }
+
+    fn caret_x(&self, lines: &[(usize, usize)], fonts: &FontSet, font: Option<&Font>, font_size: u16) -> f32 {
+        let (start, _) = lines[Self::line_of(lines, self.caret)];
+        fonts.measure_text(&self.value[start..self.caret], font, font_size, 1.0).width
+    }
+
+    /// Moves the caret to `to`, extending the selection if `select`, otherwise dropping it.
+    fn move_caret(&mut self, to: usize, select: bool) {
+        match select {
+            true => {
+                self.anchor.get_or_insert(self.caret);
+            }
+            _ => self.anchor = None,
+        }
+        self.caret = to;
+    }
+
+    fn remove_selection(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            self.value.replace_range(start..end, "");
+            self.caret = start;
+        }
+        self.anchor = None;
+    }
+
+    fn insert(&mut self, text: &str) {
+        self.remove_selection();
+        self.value.insert_str(self.caret, text);
+        self.caret += text.len();
+    }
+
+    /// Whether `key` acts this frame: once when it's pressed, then repeatedly while it's held.
+    fn key_fires(&mut self, input: &UiInput, key: KeyCode) -> bool {
+        if input.is_key_pressed(key) {
+            self.last_keycode = Some(key);
+            self.key_repeat_timer = self.repeat_delay;
+            return true;
+        }
+
+        if self.last_keycode == Some(key) && input.is_key_down(key) {
+            self.key_repeat_timer -= input.frame_time;
+            if self.key_repeat_timer <= 0.0 {
+                self.key_repeat_timer += self.repeat_interval;
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Keeps the caret in view after it moves.
+    fn scroll_to_caret(&mut self, lines: &[(usize, usize)], caret_x: f32, line_height: f32) {
+        let inner = self.size - PADDING * 2.0;
+        let top = Self::line_of(lines, self.caret) as f32 * line_height;
+
+        if top < self.scroll.y {
+            self.scroll.y = top;
+        } else if top + line_height > self.scroll.y + inner.y {
+            self.scroll.y = top + line_height - inner.y;
+        }
+
+        if caret_x < self.scroll.x {
+            self.scroll.x = caret_x;
+        } else if caret_x > self.scroll.x + inner.x {
+            self.scroll.x = caret_x - inner.x;
+        }
+    }
+}
+
+impl Widget for TextEditor {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn as_any_mut(&mut self) -> &mut (dyn Any + 'static) {
+        self
+    }
+
+    fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
+        let font = info.theme.font_for(FontStyle::Monospace);
+        let font_size = info.theme.font_size;
+        let fonts = &info.theme.fonts;
+        let line_height = Self::line_height(info.theme);
+        let char_dim = fonts.measure_text("A", font, font_size, 1.0);
+
+        let vertical_height = match info.same_line {
+            true => 0.0,
+            _ => info.rect.h,
+        };
+        let rect = Rect::new(info.rect.x, info.rect.y + vertical_height, self.size.x, self.size.y);
+
+        info.draw_list.draw_rounded_rect(
+            rect.x,
+            rect.y,
+            rect.w,
+            rect.h,
+            info.theme.frame_rounding,
+            match self.hovered {
+                true => info.theme.textbox_hover,
+                _ => info.theme.textbox,
+            },
+        );
+
+        info.draw_list.draw_rectangle_lines(
+            rect.x,
+            rect.y,
+            rect.w,
+            rect.h,
+            2.0,
+            match (self.editing, self.hovered) {
+                (true, _) => info.theme.textbox_edit_stroke,
+                (_, true) => info.theme.textbox_hover_stroke,
+                _ => info.theme.textbox_stroke,
+            },
+        );
+
+        let lines = &self.lines;
+        let selection = self.selection();
+        let caret_line = TextEditor::line_of(lines, self.caret);
+        let origin = rect.point() + PADDING - self.scroll;
+
+        let old_clip = info.draw_list.push_clip(Rect::new(
+            rect.x + 2.0,
+            rect.y + 2.0,
+            rect.w - 4.0,
+            rect.h - 4.0,
+        ));
+
+        let first = (self.scroll.y / line_height).floor().max(0.0) as usize;
+        let visible = (self.size.y / line_height).ceil() as usize + 1;
+
+        for (l, &(start, end)) in lines.iter().enumerate().skip(first).take(visible) {
+            let y = origin.y + l as f32 * line_height;
+
+            // DRAW SELECTION
+            if let Some((sel_start, sel_end)) = selection {
+                if sel_start <= end && sel_end >= start && sel_start != sel_end {
+                    let from = sel_start.max(start);
+                    let to = sel_end.min(end);
+                    let x = fonts.measure_text(&self.value[start..from], font, font_size, 1.0).width;
+                    let mut w = fonts.measure_text(&self.value[from..to], font, font_size, 1.0).width;
+
+                    // Show that the newline at the end of the line is selected too
+                    if sel_end > end && end < self.value.len() {
+                        w += char_dim.width / 2.0;
+                    }
+
+                    info.draw_list.draw_rectangle(origin.x + x, y, w, line_height, info.theme.selection);
+                }
+            }
+
+            for _ in 0..4 {
+                info.draw_list.draw_text_ex(
+                    &self.value[start..end],
+                    origin.x,
+                    y + (line_height + char_dim.height) / 2.0,
+                    TextParams {
+                        font,
+                        font_size,
+                        color: info.theme.text,
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+
+        // DRAW CARET
+        if self.editing && (self.dragging || info.time % 1.0 < 0.5) {
+            let x = origin.x + self.caret_x(lines, fonts, font, font_size);
+            let y = origin.y + caret_line as f32 * line_height;
+            info.draw_list.draw_line(x, y + 1.0, x, y + line_height - 1.0, 1.0, info.theme.caret);
+        }
+
+        info.draw_list.restore_clip(old_clip);
+
+        // DRAW SCROLLBAR
+        let content_h = lines.len() as f32 * line_height + PADDING * 2.0;
+        if content_h > self.size.y {
+            let thickness = info.theme.scrollbar_thickness.min(6.0);
+            let thumb_h = (self.size.y / content_h) * self.size.y;
+            let max_scroll = content_h - self.size.y;
+
+            info.draw_list.draw_rectangle(
+                rect.x + rect.w - thickness - 2.0,
+                rect.y + (self.scroll.y / max_scroll) * (self.size.y - thumb_h),
+                thickness,
+                thumb_h,
+                info.theme.scrollbar,
+            );
+        }
+
+        Some(self.size)
+    }
+
+    fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
+        self.caret = floor_char_boundary(&self.value, self.caret);
+        self.anchor = self.anchor.map(|a| floor_char_boundary(&self.value, a));
+
+        let before = self.state();
+        let old_caret = self.caret;
+        // What this frame's edit counts as for undo, or `None` for an undo or redo
+        let mut edit_kind = Some(EditKind::Typing);
+
+        let font = info.theme.font_for(FontStyle::Monospace);
+        let font_size = info.theme.font_size;
+        let line_height = Self::line_height(info.theme);
+
+        let vertical_height = match info.same_line {
+            true => 0.0,
+            _ => info.rect.h,
+        };
+        let rect = Rect::new(info.rect.x, info.rect.y + vertical_height, self.size.x, self.size.y);
+        let size = Some(self.size);
+
+        self.relayout(&info.theme.fonts, font, font_size);
+        let mut lines = self.lines.clone();
+        let hit = |editor: &TextEditor, lines: &[(usize, usize)], mouse: Vec2| {
+            let local = mouse - rect.point() - PADDING + editor.scroll;
+            let l = ((local.y / line_height).floor().max(0.0) as usize).min(lines.len() - 1);
+            editor.hit_line(lines, l, local.x, &info.theme.fonts, font, font_size)
+        };
+
+        if !info.mouse_action.taken && info.hover && rect.contains(info.mouse) {
+            self.hovered = true;
+            info.mouse_action.cursor = Some(CursorIcon::Text);
+
+        } else {
+            self.hovered = false;
+        }
+
+        if info.mouse_action.taken {
+            self.editing = false;
+        }
+
+        // The wheel scrolls the editor instead of the window, while there's anything to scroll
+        let wheel = info.input.mouse_wheel().1;
+        let content_h = lines.len() as f32 * line_height + PADDING * 2.0;
+        if self.hovered && wheel != 0.0 && content_h > self.size.y {
+            self.scroll.y -= wheel;
+            info.mouse_action.taken = true;
+        }
+
+        let shift = info.input.is_key_down(KeyCode::LeftShift) || info.input.is_key_down(KeyCode::RightShift);
+        let ctrl = info.input.is_key_down(KeyCode::LeftControl) || info.input.is_key_down(KeyCode::RightControl);
+
+        if self.dragging && info.input.is_mouse_button_down(Left) {
+            self.caret = hit(self, &lines, info.mouse);
+            self.target_x = None;
+        } else {
+            self.dragging = false;
+        }
+
+        if !info.mouse_action.taken && info.input.is_mouse_button_pressed(Left) && info.hover {
+            if self.hovered {
+                info.request_focus = true;
+                self.editing = true;
+                self.dragging = true;
+
+                self.clicks = match self.last_click {
+                    Some(last) if info.input.time - last < MULTI_CLICK_TIME => self.clicks + 1,
+                    _ => 1,
+                };
+                self.last_click = Some(info.input.time);
+
+                let to = hit(self, &lines, info.mouse);
+                match self.clicks {
+                    1 => {
+                        self.move_caret(to, shift);
+                        self.anchor.get_or_insert(to);
+                    }
+                    // Double-click selects a word, triple-click everything
+                    2 => {
+                        let (start, end) = word_at(&self.value, to);
+                        self.anchor = Some(start);
+                        self.caret = end;
+                        self.dragging = false;
+                    }
+                    _ => {
+                        self.anchor = Some(0);
+                        self.caret = self.value.len();
+                        self.dragging = false;
+                    }
+                }
+                self.target_x = None;
+            } else {
+                self.editing = false;
+            }
+        } else if info.input.is_mouse_button_pressed(Left) {
+            self.editing = false;
+        }
+
+        info.focus_rect = Some(rect);
+
+        // Keyboard focus: Enter/Space starts editing, Escape stops, and moving focus away stops too
+        if info.focused {
+            if !self.editing && info.nav.activate {
+                self.editing = true;
+                self.anchor = None;
+                return size;
+            } else if self.editing && info.nav.cancel {
+                self.editing = false;
+                info.focus.nav_used = true;
+            }
+        } else if self.editing && !self.dragging {
+            self.editing = false;
+        }
+
+        if self.editing {
+            info.focus.text_input = true;
+
+            let input = info.input;
+            let fonts = &info.theme.fonts;
+            let page = ((self.size.y - PADDING * 2.0) / line_height).floor().max(1.0) as usize;
+            let line = Self::line_of(&lines, self.caret);
+
+            // How many lines Up/Down/PageUp/PageDown move by
+            let lines_moved = if self.key_fires(input, KeyCode::Up) {
+                Some(-1)
+            } else if self.key_fires(input, KeyCode::PageUp) {
+                Some(-(page as isize))
+            } else if self.key_fires(input, KeyCode::Down) {
+                Some(1)
+            } else if self.key_fires(input, KeyCode::PageDown) {
+                Some(page as isize)
+            } else {
+                None
+            };
+
+            if ctrl {
+                if input.is_key_pressed(KeyCode::A) {
+                    self.anchor = Some(0);
+                    self.caret = self.value.len();
+                }
+
+                if input.is_key_pressed(KeyCode::C) || input.is_key_pressed(KeyCode::X) {
+                    if let Some((start, end)) = self.selection() {
+                        self.clipboard.set_text(self.value[start..end].to_string());
+
+                        if input.is_key_pressed(KeyCode::X) {
+                            self.remove_selection();
+                            edit_kind = Some(EditKind::Other);
+                        }
+                    }
+                }
+
+                if input.is_key_pressed(KeyCode::V) {
+                    if let Some(text) = self.clipboard.get_text() {
+                        self.insert(&text.replace("\r\n", "\n"));
+                        edit_kind = Some(EditKind::Other);
+                    }
+                }
+
+                let redo = input.is_key_pressed(KeyCode::Y) || (shift && input.is_key_pressed(KeyCode::Z));
+                if redo || input.is_key_pressed(KeyCode::Z) {
+                    let state = match redo {
+                        true => self.history.redo(self.state()),
+                        _ => self.history.undo(self.state()),
+                    };
+                    if let Some(state) = state {
+                        self.restore(state);
+                    }
+                    edit_kind = None;
+                }
+            }
+
+            // Ctrl moves by words, Shift extends the selection
+            if self.key_fires(input, KeyCode::Left) {
+                let to = match (self.selection(), shift, ctrl) {
+                    (Some((start, _)), false, false) => start,
+                    (_, _, true) => prev_word(&self.value, self.caret),
+                    _ => prev_char(&self.value, self.caret),
+                };
+                self.move_caret(to, shift);
+                self.target_x = None;
+            } else if self.key_fires(input, KeyCode::Right) {
+                let to = match (self.selection(), shift, ctrl) {
+                    (Some((_, end)), false, false) => end,
+                    (_, _, true) => next_word(&self.value, self.caret),
+                    _ => next_char(&self.value, self.caret),
+                };
+                self.move_caret(to, shift);
+                self.target_x = None;
+            } else if let Some(by) = lines_moved {
+                // Past the first or last line goes to the start or end of the text
+                let x = *self.target_x.get_or_insert(self.caret_x(&lines, fonts, font, font_size));
+                let to = match line.checked_add_signed(by) {
+                    Some(l) if l < lines.len() => self.hit_line(&lines, l, x, fonts, font, font_size),
+                    _ if by < 0 => 0,
+                    _ => self.value.len(),
+                };
+                self.move_caret(to, shift);
+            } else if input.is_key_pressed(KeyCode::Home) {
+                let to = match ctrl {
+                    true => 0,
+                    _ => lines[line].0,
+                };
+                self.move_caret(to, shift);
+                self.target_x = None;
+            } else if input.is_key_pressed(KeyCode::End) {
+                let to = match ctrl {
+                    true => self.value.len(),
+                    _ => self.line_end(&lines, line),
+                };
+                self.move_caret(to, shift);
+                self.target_x = None;
+            } else if self.key_fires(input, KeyCode::Backspace) {
+                if self.selection().is_none() {
+                    self.anchor = Some(match ctrl {
+                        true => prev_word(&self.value, self.caret),
+                        _ => prev_char(&self.value, self.caret),
+                    });
+                }
+                self.remove_selection();
+                self.target_x = None;
+                edit_kind = Some(EditKind::Deleting);
+            } else if self.key_fires(input, KeyCode::Delete) {
+                if self.selection().is_none() {
+                    self.anchor = Some(match ctrl {
+                        true => next_word(&self.value, self.caret),
+                        _ => next_char(&self.value, self.caret),
+                    });
+                }
+                self.remove_selection();
+                self.target_x = None;
+                edit_kind = Some(EditKind::Deleting);
+            } else if self.key_fires(input, KeyCode::Enter) || self.key_fires(input, KeyCode::KpEnter) {
+                self.insert("\n");
+                self.target_x = None;
+            }
+
+            if !ctrl {
+                for &c in input.chars.iter() {
+                    if !c.is_control() {
+                        self.insert(c.encode_utf8(&mut [0; 4]));
+                        self.target_x = None;
+                    }
+                }
+            }
+        } else {
+            self.last_keycode = None;
+            self.anchor = None;
+        }
+
+        let edited = self.value != before.value;
+        if edited {
+            if let Some(kind) = edit_kind {
+                self.history.record(before, kind, self.caret, info.input.time);
+            }
+            self.relayout(&info.theme.fonts, font, font_size);
+            lines = self.lines.clone();
+        }
+
+        if self.caret != old_caret || edited {
+            let caret_x = self.caret_x(&lines, &info.theme.fonts, font, font_size);
+            self.scroll_to_caret(&lines, caret_x, line_height);
+        }
+
+        let content_h = lines.len() as f32 * line_height + PADDING * 2.0;
+        self.scroll.y = self.scroll.y.clamp(0.0, (content_h - self.size.y).max(0.0));
+        if self.word_wrap {
+            self.scroll.x = 0.0;
+        }
+
+        size
+    }
+}
@@ -1,3 +1,4 @@
+use crate::ui::fonts::FontStyle;
 use crate::widgets::widget::Widget;
 use crate::widgets::widget_holder::{RenderInfo, UpdateInfo};
 use macroquad::math::u16;
@@ -9,6 +10,8 @@ pub struct TextEx {
     pub color: Color,
     pub font_size: u16,
     pub font: Option<Font>,
+    /// Which of the theme's fonts to use when `font` is `None`.
+    pub style: FontStyle,
 }
 
 impl TextEx {
@@ -18,8 +21,14 @@ impl TextEx {
             color,
             font_size,
             font,
+            style: FontStyle::Regular,
         }
     }
+
+    pub fn set_style(&mut self, style: FontStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
 }
 
 impl Widget for TextEx {
@@ -32,17 +41,14 @@ impl Widget for TextEx {
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
         let font = match &self.font {
-            None => match &info.font {
-                Some(f) => Some(f),
-                _ => None,
-            },
+            None => info.theme.font_for(self.style),
             custom_font => Some(&custom_font.clone().unwrap()),
         };
 
-        let text_dim = measure_text(&self.value.to_string(), font, self.font_size, 1.0);
+        let text_dim = info.theme.fonts.measure_text(&self.value.to_string(), font, self.font_size, 1.0);
 
         for _ in 0..4 {
-            draw_text_ex(
+            info.draw_list.draw_text_ex(
                 &self.value.to_string(),
                 info.rect.x,
                 info.rect.y + text_dim.height + info.rect.h,
@@ -60,14 +66,11 @@ impl Widget for TextEx {
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
         let font = match &self.font {
-            None => match &info.font {
-                Some(f) => Some(f),
-                _ => None,
-            },
+            None => info.theme.font_for(self.style),
             custom_font => Some(&custom_font.clone().unwrap()),
         };
 
-        let text_dim = measure_text(&self.value.to_string(), font, self.font_size, 1.0);
+        let text_dim = info.theme.fonts.measure_text(&self.value.to_string(), font, self.font_size, 1.0);
 
         Some(vec2(text_dim.width, text_dim.height))
     }
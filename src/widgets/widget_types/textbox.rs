@@ -1,17 +1,26 @@
-﻿use crate::widget_holder::{RenderInfo, UpdateInfo};
-use crate::Widget;
-use arboard::Clipboard;
+﻿use crate::ui::fonts::FontSet;
+use crate::widget_holder::{RenderInfo, UpdateInfo};
+use crate::widgets::widget_types::text_edit::{
+    floor_char_boundary, next_char, next_word, prev_char, prev_word, word_at, EditKind,
+    TextClipboard, TextState, UndoStack, MULTI_CLICK_TIME,
+};
+use crate::{Error, FontStyle, Widget};
 use macroquad::input::MouseButton::Left;
-use macroquad::miniquad::window::set_mouse_cursor;
 use macroquad::miniquad::CursorIcon;
 use macroquad::prelude::*;
 use std::any::Any;
+use std::borrow::Cow;
 
+/// `caret` and the selection are byte offsets into `value`, always on char boundaries.
 pub struct TextBox {
     pub value: String,
     pub caret: usize,
     pub label: Option<String>,
     pub caret_changed: bool,
+    /// Draws every character as a bullet, and turns off copy and cut.
+    pub password: bool,
+    /// Characters it rejects can't be typed or pasted in.
+    filter: Option<Box<dyn Fn(char) -> bool>>,
 
     selection_start: i32,
     selection_end: i32,
@@ -21,7 +30,10 @@ pub struct TextBox {
     pressed: bool,
     dragging: Option<usize>,
     text_hover: Option<usize>,
-    clipboard_queue: Option<String>,
+    /// When the last click was, and how many came before it in quick succession.
+    last_click: Option<f64>,
+    clicks: u32,
+    clipboard: TextClipboard,
 
     // repeat state
     last_keycode: Option<KeyCode>,
@@ -35,7 +47,7 @@ pub struct TextBox {
     min_repeat_interval: f32,
     acceleration: f32,
     
-    history: Vec<String>
+    history: UndoStack,
 }
 
 impl TextBox {
@@ -45,6 +57,8 @@ impl TextBox {
             value: default_text,
             caret: 0,
             caret_changed: false,
+            password: false,
+            filter: None,
             
             hovered: false,
             pressed: false,
@@ -54,7 +68,9 @@ impl TextBox {
             selection_end: -1,
             text_hover: None,
             dragging: None,
-            clipboard_queue: None,
+            last_click: None,
+            clicks: 0,
+            clipboard: TextClipboard::default(),
 
             last_keycode: None,
             last_char: None,
@@ -66,14 +82,159 @@ impl TextBox {
             min_repeat_interval: 0.01,
             acceleration: 0.85,
             
-            history: vec![]
+            history: UndoStack::new(100),
         }
     }
 
+    pub fn set_password(&mut self, password: bool) -> &mut Self {
+        self.password = password;
+        self
+    }
+
+    /// Only lets in characters `filter` returns `true` for. Text that's already there is left alone.
+    pub fn set_filter(&mut self, filter: impl Fn(char) -> bool + 'static) -> &mut Self {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    pub fn clear_filter(&mut self) -> &mut Self {
+        self.filter = None;
+        self
+    }
+
+    fn accepts(&self, c: char) -> bool {
+        !c.is_control() && self.filter.as_ref().is_none_or(|f| f(c))
+    }
+
+    /// `value` up to byte `end` as it's drawn: itself, or bullets in password mode.
+    fn shown(&self, end: usize, fonts: &FontSet) -> Cow<'_, str> {
+        if !self.password {
+            return Cow::Borrowed(&self.value[..end]);
+        }
+
+        // macroquad's built-in font is ASCII only
+        let has_bullet = fonts.get(FontStyle::Regular).is_some_and(|f| f.has_glyph('•'))
+            || fonts.fallbacks().iter().any(|f| f.has_glyph('•'));
+        let bullet = match has_bullet {
+            true => "•",
+            _ => "*",
+        };
+
+        Cow::Owned(bullet.repeat(self.value[..end].chars().count()))
+    }
+
+    /// How many steps Ctrl+Z can go back. Defaults to 100.
+    pub fn set_undo_depth(&mut self, depth: usize) -> &mut Self {
+        self.history.set_depth(depth);
+        self
+    }
+
+    /// Why the clipboard couldn't be used the last time copy or paste was, if it couldn't. They do nothing until it can.
+    pub fn clipboard_error(&self) -> Option<&Error> {
+        self.clipboard.error.as_ref()
+    }
+
+    fn state(&self) -> TextState {
+        TextState {
+            value: self.value.clone(),
+            caret: self.caret,
+            selection: match self.selection_start > -1 && self.selection_end > -1 {
+                true => Some((self.selection_start as usize, self.selection_end as usize)),
+                _ => None,
+            },
+        }
+    }
+
+    fn restore(&mut self, state: TextState) {
+        self.value = state.value;
+        self.caret = state.caret;
+        (self.selection_start, self.selection_end) = match state.selection {
+            Some((start, end)) => (start as i32, end as i32),
+            _ => (-1, -1),
+        };
+    }
+
+    /// Adds an undo step if the value changed since `before`.
+    fn record_edit(&mut self, before: TextState, kind: EditKind, time: f64) {
+        if self.value != before.value {
+            self.history.record(before, kind, self.caret, time);
+        }
+    }
+
+    /// The selected byte range, if anything is selected.
+    fn selection(&self) -> Option<(usize, usize)> {
+        match self.selection_start > -1 && self.selection_end > -1 {
+            true => Some((
+                self.selection_start.min(self.selection_end) as usize,
+                self.selection_start.max(self.selection_end) as usize,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Removes the selected text, if any, and puts the caret where it was.
+    fn remove_selection(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            self.value.replace_range(start..end, "");
+            self.caret = start;
+        }
+
+        self.selection_start = -1;
+        self.selection_end = -1;
+    }
+
+    /// Moves the caret to `to`, extending the selection if `select`, otherwise dropping it.
+    fn move_caret(&mut self, to: usize, select: bool) {
+        if select {
+            if self.selection_start < 0 {
+                self.selection_start = self.caret as i32;
+            }
+            self.selection_end = to as i32;
+        }
+
+        if !select || self.selection_start == self.selection_end {
+            self.selection_start = -1;
+            self.selection_end = -1;
+        }
+
+        self.caret = to;
+    }
+
+    /// Moves the caret a character, or a word with `word`. Without `select`, a selection
+    /// collapses to the side it's moved towards instead.
+    fn step_caret(&mut self, right: bool, word: bool, select: bool) {
+        let to = match (self.selection(), right, word) {
+            (Some((start, _)), false, false) if !select => start,
+            (Some((_, end)), true, false) if !select => end,
+            (_, false, false) => prev_char(&self.value, self.caret),
+            (_, true, false) => next_char(&self.value, self.caret),
+            (_, false, true) => prev_word(&self.value, self.caret),
+            (_, true, true) => next_word(&self.value, self.caret),
+        };
+        self.move_caret(to, select);
+    }
+
+    /// Backspace (or Delete with `forward`): removes the selection, or else the character or word next to the caret.
+    fn delete(&mut self, forward: bool, word: bool) {
+        if self.selection().is_some() {
+            self.remove_selection();
+            return;
+        }
+
+        let (start, end) = match (forward, word) {
+            (false, false) => (prev_char(&self.value, self.caret), self.caret),
+            (false, true) => (prev_word(&self.value, self.caret), self.caret),
+            (true, false) => (self.caret, next_char(&self.value, self.caret)),
+            (true, true) => (self.caret, next_word(&self.value, self.caret)),
+        };
+        self.value.replace_range(start..end, "");
+        self.caret = start;
+    }
+
     fn get_text_hover(&mut self, info: &mut UpdateInfo, font: Option<&Font>, label_width: f32, vertical_height: f32, char_dim: TextDimensions) {
         self.text_hover = None;
-        for i in (0..self.value.len()).rev() {
-            let text_hover_width = measure_text(&self.value[0..i + 1], font, 14, 1.0);
+        for (i, c) in self.value.char_indices().rev() {
+            let text_hover_width = info.theme.fonts.measure_text(&self.shown(i + c.len_utf8(), &info.theme.fonts), font, info.theme.font_size, 1.0);
             let rect = Rect::new(
                 label_width + info.rect.x,
                 info.rect.y + vertical_height,
@@ -100,12 +261,12 @@ impl Widget for TextBox {
 
     fn render(&self, info: &mut RenderInfo) -> Option<Vec2> {
         let font = info.font.into();
-        let char_dim = measure_text("A", font, 14, 1.0);
-        let caret_dim = measure_text(&self.value[0..self.caret.min(self.value.len())], font, 14, 1.0);
-        let text_dim = measure_text(&self.value, font, 14, 1.0);
+        let char_dim = info.theme.fonts.measure_text("A", font, info.theme.font_size, 1.0);
+        let caret_dim = info.theme.fonts.measure_text(&self.shown(floor_char_boundary(&self.value, self.caret), &info.theme.fonts), font, info.theme.font_size, 1.0);
+        let text_dim = info.theme.fonts.measure_text(&self.shown(self.value.len(), &info.theme.fonts), font, info.theme.font_size, 1.0);
 
         let label_width = match &self.label {
-            Some(s) => measure_text(s, font, 14, 1.0).width + 10.0,
+            Some(s) => info.theme.fonts.measure_text(s, font, info.theme.font_size, 1.0).width + 10.0,
             _ => 0.0,
         };
 
@@ -114,79 +275,79 @@ impl Widget for TextBox {
             _ => info.rect.h,
         };
 
-        draw_rectangle(
+        info.draw_list.draw_rectangle(
             info.rect.x + label_width,
             info.rect.y + vertical_height,
             (text_dim.width + 10.0).max(100.0),
             char_dim.height + 10.0,
             match self.hovered {
-                true => Color::new(1.0, 1.0, 1.0, 0.4),
-                _ => Color::new(1.0, 1.0, 1.0, 0.2),
+                true => info.theme.textbox_hover,
+                _ => info.theme.textbox,
             },
         );
 
-        draw_rectangle_lines(
+        info.draw_list.draw_rectangle_lines(
             info.rect.x + label_width,
             info.rect.y + vertical_height,
             (text_dim.width + 10.0).max(100.0),
             char_dim.height + 10.0,
             2.0,
             match self.hovered {
-                true => Color::new(0.1, 0.47, 0.95, 0.8),
-                _ => WHITE.with_alpha(0.5),
+                true => info.theme.textbox_hover_stroke,
+                _ => info.theme.textbox_stroke,
             },
         );
 
         if self.editing {
-            draw_rectangle_lines(
+            info.draw_list.draw_rectangle_lines(
                 info.rect.x + label_width,
                 info.rect.y + vertical_height,
                 (text_dim.width + 10.0).max(100.0),
                 char_dim.height + 10.0,
                 2.0,
-                Color::new(0.2, 0.6, 1.0, 1.0),
+                info.theme.textbox_edit_stroke,
             );
 
             // DRAW CARET
-            if self.caret_changed || get_time() % 1.0 < 0.5 {
-                draw_line(
+            if self.caret_changed || info.time % 1.0 < 0.5 {
+                info.draw_list.draw_line(
                     info.rect.x + label_width + caret_dim.width + 5.0,
                     info.rect.y + vertical_height + 2.0,
                     info.rect.x + label_width + caret_dim.width + 5.0,
                     info.rect.y + vertical_height + char_dim.height + 8.0,
                     1.0,
-                    WHITE,
+                    info.theme.caret,
                 );
             }
 
             // DRAW SELECTION
             if self.selection_start > -1 && self.selection_end > -1 {
-                let start = self.selection_start.min(self.selection_end);
-                let end = self.selection_start.max(self.selection_end);
+                let start = floor_char_boundary(&self.value, self.selection_start.min(self.selection_end) as usize);
+                let end = floor_char_boundary(&self.value, self.selection_start.max(self.selection_end) as usize);
 
-                let start_pos = measure_text(&self.value[0..start as usize], font, 14, 1.0).width;
+                let start_pos = info.theme.fonts.measure_text(&self.shown(start, &info.theme.fonts), font, info.theme.font_size, 1.0).width;
                 let length =
-                    measure_text(&self.value[0..end as usize], font, 14, 1.0).width - start_pos;
+                    info.theme.fonts.measure_text(&self.shown(end, &info.theme.fonts), font, info.theme.font_size, 1.0).width - start_pos;
 
-                draw_rectangle(
+                info.draw_list.draw_rectangle(
                     info.rect.x + label_width + 5.0 + start_pos,
                     info.rect.y + vertical_height + 2.0,
                     length,
                     char_dim.height + 6.0,
-                    DARKBLUE.with_alpha(0.88),
+                    info.theme.selection,
                 );
             }
         }
 
         for _ in 0..4 {
-            draw_text_ex(
-                &self.value,
+            info.draw_list.draw_text_ex(
+                &self.shown(self.value.len(), &info.theme.fonts),
                 info.rect.x + label_width + 5.0,
                 info.rect.y + vertical_height + char_dim.height + 4.0,
                 TextParams {
                     font,
-                    font_size: 14,
-                    color: WHITE,
+                    font_size: info.theme.font_size,
+                    color: info.theme.text,
                     ..Default::default()
                 },
             );
@@ -195,14 +356,14 @@ impl Widget for TextBox {
         // DRAW OPTIONAL LABEL
         if let Some(s) = &self.label {
             for _ in 0..4 {
-                draw_text_ex(
+                info.draw_list.draw_text_ex(
                     s,
                     info.rect.x,
                     info.rect.y + vertical_height + char_dim.height + 4.0,
                     TextParams {
                         font,
-                        font_size: 14,
-                        color: WHITE,
+                        font_size: info.theme.font_size,
+                        color: info.theme.text,
                         ..Default::default()
                     },
                 );
@@ -216,26 +377,17 @@ impl Widget for TextBox {
     }
 
     fn update(&mut self, info: &mut UpdateInfo) -> Option<Vec2> {
-        let mut clipboard = Clipboard::new().unwrap();
-        if let Ok(s) = clipboard.get_text() {
-            if let Some(text) = &self.clipboard_queue {
-                if text == &s {
-                    self.clipboard_queue = None;
-                }
-            }
-        }
-        if let Some(text) = &self.clipboard_queue {
-            clipboard.set_text(text).unwrap();
-        }
-        
-        let old_text = self.value.clone();
-        let dt = get_frame_time();
+        self.caret = floor_char_boundary(&self.value, self.caret);
+
+        let before = self.state();
+        let dt = info.input.frame_time;
+        let time = info.input.time;
 
         let font = info.font.into();
-        let char_dim = measure_text("A", font, 14, 1.0);
-        let text_dim = measure_text(&self.value, font, 14, 1.0);
+        let char_dim = info.theme.fonts.measure_text("A", font, info.theme.font_size, 1.0);
+        let text_dim = info.theme.fonts.measure_text(&self.shown(self.value.len(), &info.theme.fonts), font, info.theme.font_size, 1.0);
         let label_width = match &self.label {
-            Some(s) => measure_text(s, font, 14, 1.0).width + 10.0,
+            Some(s) => info.theme.fonts.measure_text(s, font, info.theme.font_size, 1.0).width + 10.0,
             _ => 0.0,
         };
 
@@ -253,9 +405,9 @@ impl Widget for TextBox {
 
         let size = Some(vec2(rect.w + label_width, rect.h));
 
-        if !info.mouse_action.taken && info.hover && rect.contains(mouse_position().into()) {
+        if !info.mouse_action.taken && info.hover && rect.contains(info.mouse) {
             self.hovered = true;
-            set_mouse_cursor(CursorIcon::Text);
+            info.mouse_action.cursor = Some(CursorIcon::Text);
         } else {
             self.hovered = false;
         }
@@ -266,36 +418,50 @@ impl Widget for TextBox {
 
         let old_caret = self.caret;
         
-        if is_mouse_button_down(Left) && self.dragging.is_some() {
+        if info.input.is_mouse_button_down(Left) && self.dragging.is_some() {
             let drag_start = self.dragging.unwrap();
             
             self.get_text_hover(info, font, label_width, vertical_height, char_dim);
             if let Some(drag_end) = self.text_hover {
-                if drag_start != drag_end {
-                    self.selection_start = drag_start.min(drag_end) as i32;
-                    self.selection_end = drag_start.max(drag_end) as i32;
-                    self.caret = drag_end;
-                }
+                self.caret = drag_start;
+                self.move_caret(drag_end, true);
             } else if self.selection_start > -1 {
                 self.selection_end = self.value.len() as i32;
+                self.caret = self.value.len();
             }
         } else {
             self.dragging = None;
         }
 
         self.pressed = false;
-        if !info.mouse_action.taken && is_mouse_button_pressed(Left) && info.hover {
+        if !info.mouse_action.taken && info.input.is_mouse_button_pressed(Left) && info.hover {
             if self.hovered {
                 self.pressed = true;
+                info.request_focus = true;
+
+                self.clicks = match self.last_click {
+                    Some(last) if info.input.time - last < MULTI_CLICK_TIME => self.clicks + 1,
+                    _ => 1,
+                };
+                self.last_click = Some(info.input.time);
 				
-				if !self.editing {
+				if !self.editing || self.clicks >= 3 {
 					self.selection_start = 0;
 					self.selection_end = self.value.len() as i32;
 					self.caret = self.value.len();
 					self.editing = true;
 				} else {
                     self.get_text_hover(info, font, label_width, vertical_height, char_dim);
-					if let Some(t) = self.text_hover {
+                    if self.clicks == 2 {
+                        // Double-click selects the word under the mouse, or everything in a password, whose words are hidden
+                        let (start, end) = match self.password {
+                            true => (0, self.value.len()),
+                            _ => word_at(&self.value, self.text_hover.unwrap_or(self.value.len())),
+                        };
+                        self.selection_start = start as i32;
+                        self.selection_end = end as i32;
+                        self.caret = end;
+                    } else if let Some(t) = self.text_hover {
                         self.dragging = self.text_hover;
 						self.caret = t;
 						self.selection_start = -1;
@@ -310,10 +476,28 @@ impl Widget for TextBox {
             } else {
                 self.editing = false;
             }
-        } else if is_mouse_button_pressed(Left) {
+        } else if info.input.is_mouse_button_pressed(Left) {
             self.editing = false;
         }
         
+        info.focus_rect = Some(rect);
+
+        // Keyboard focus: Enter/Space starts editing, Enter/Escape stops, and moving focus away stops too
+        if info.focused {
+            if !self.editing && info.nav.activate {
+                self.selection_start = 0;
+                self.selection_end = self.value.len() as i32;
+                self.caret = self.value.len();
+                self.editing = true;
+                return size;
+            } else if self.editing && (info.nav.cancel || info.input.is_key_pressed(KeyCode::Enter)) {
+                self.editing = false;
+                info.focus.nav_used = info.nav.cancel;
+            }
+        } else if self.editing && !self.pressed {
+            self.editing = false;
+        }
+
         if !self.editing {
             self.last_keycode = None;
             self.last_char = None;
@@ -322,296 +506,140 @@ impl Widget for TextBox {
             return size;
         }
 
-        if is_key_down(KeyCode::LeftControl) {
-            if is_key_pressed(KeyCode::A) {
+        info.focus.text_input = true;
+
+        let ctrl = info.input.is_key_down(KeyCode::LeftControl) || info.input.is_key_down(KeyCode::RightControl);
+        let shift = info.input.is_key_down(KeyCode::LeftShift) || info.input.is_key_down(KeyCode::RightShift);
+
+        if ctrl {
+            if info.input.is_key_pressed(KeyCode::A) {
                 self.selection_start = 0;
                 self.selection_end = self.value.len() as i32;
                 self.caret = self.value.len();
             }
 
-            if is_key_pressed(KeyCode::V) {
-                if let Ok(text) = clipboard.get_text() {
-                    self.value.insert_str(self.caret.min(self.value.len()), &text);
+            if info.input.is_key_pressed(KeyCode::V) {
+                if let Some(text) = self.clipboard.get_text() {
+                    let text: String = text.chars().filter(|c| self.accepts(*c)).collect();
+                    self.remove_selection();
+                    self.value.insert_str(self.caret, &text);
                     self.caret += text.len();
                 }
             }
-            
-            if is_key_pressed(KeyCode::C) {
-                if self.selection_start >= 0 && self.selection_end >= 0 {
-                    let start = self.selection_start.min(self.selection_end) as usize;
-                    let end = self.selection_start.max(self.selection_end) as usize;
-                    let text = &self.value[start..end];
-                    
-                    self.clipboard_queue = Some(text.to_string());
+
+            if let (Some((start, end)), false) = (self.selection(), self.password) {
+                if info.input.is_key_pressed(KeyCode::C) || info.input.is_key_pressed(KeyCode::X) {
+                    self.clipboard.set_text(self.value[start..end].to_string());
                 }
-            }
-            
-            if is_key_pressed(KeyCode::X) {
-                if self.selection_start >= 0 && self.selection_end >= 0 {
-                    let start = self.selection_start.min(self.selection_end) as usize;
-                    let end = self.selection_start.max(self.selection_end) as usize;
-                    
-                    let text = &self.value[start..end];
-                    self.clipboard_queue = Some(text.to_string());
-                    
-                    let mut new_value = String::from(&self.value[0..start]);
-                    new_value.push_str(&self.value[end..]);
-                    self.value = new_value;
-                    
-                    self.caret = self.selection_start as usize;
-                    self.selection_start = -1;
-                    self.selection_end = -1;
+
+                if info.input.is_key_pressed(KeyCode::X) {
+                    self.remove_selection();
                 }
             }
 
-            if is_key_pressed(KeyCode::Z) {
-                if !self.history.is_empty() {
-                    self.value = self.history.last().unwrap().clone();
-                    self.history.pop();
+            let redo = info.input.is_key_pressed(KeyCode::Y) || (shift && info.input.is_key_pressed(KeyCode::Z));
+
+            if redo || info.input.is_key_pressed(KeyCode::Z) {
+                let state = match redo {
+                    true => self.history.redo(before),
+                    _ => self.history.undo(before),
+                };
+                if let Some(state) = state {
+                    self.restore(state);
                 }
                 return size;
             }
             
-            if self.value != old_text {
-                self.history.push(old_text.to_string());
+            if self.value != before.value {
+                self.record_edit(before, EditKind::Other, time);
+                return size;
             }
-            return size;
         }
 
         // --- immediate press handling ---
-        if is_key_pressed(KeyCode::Backspace) || is_key_pressed(KeyCode::Delete) {
-            let key = match is_key_pressed(KeyCode::Delete) {
-                true => "delete",
-                _ => "backspace"
+        if info.input.is_key_pressed(KeyCode::Backspace) || info.input.is_key_pressed(KeyCode::Delete) {
+            let key = match info.input.is_key_pressed(KeyCode::Delete) {
+                true => KeyCode::Delete,
+                _ => KeyCode::Backspace
             };
             
-            if !self.value.is_empty() {
-                if self.selection_start > -1 {
-                    let start = self.selection_start.min(self.selection_end);
-                    let end = self.selection_start.max(self.selection_end);
-                    
-                    for _ in start..end {
-                        if self.value.len() > 0 {
-                            self.value.remove(start as usize);
-                        }
-                    }
-                    
-                    self.selection_start = -1;
-                    self.selection_end = -1;
-                } else {
-                    if key == "delete" && self.value.len() > self.caret {
-                        self.value.remove(self.caret);
-                    } else if self.caret > 0 {
-                        self.value.remove(self.caret - 1);
-                        self.caret = (self.caret as i32 - 1).max(0) as usize;
-                    }
-                }
-            }
+            // With Ctrl, a whole word goes
+            self.delete(key == KeyCode::Delete, ctrl);
             
-            if self.selection_start == self.selection_end {
-                self.selection_start = -1;
-            }
-            
-            self.last_keycode = Some(match key {
-                "delete" => KeyCode::Delete,
-                _ => KeyCode::Backspace
-            });
+            self.last_keycode = Some(key);
             self.last_char = None;
             self.last_repeat_count = 0;
             self.key_repeat_timer = self.repeat_delay;
             
-            self.caret = self.caret.clamp(0, self.value.len());
-            
-            if self.value != old_text {
-                self.history.push(old_text.to_string());
-            }
-            
+            self.record_edit(before, EditKind::Deleting, time);
             return size;
         }
 
         // --- immediate press handling ---
-        if is_key_pressed(KeyCode::Left) {
-            if is_key_down(KeyCode::LeftShift) {
-                if self.selection_start < 0 {
-                    self.selection_start = self.caret as i32;
-                    self.selection_end = self.caret as i32 - 1;
-                } else {
-                    self.selection_end = self.caret as i32 - 1;
-                }
-                self.selection_start = self.selection_start.clamp(0, self.value.len() as i32);
-                self.selection_end = self.selection_end.clamp(0, self.value.len() as i32);
-            } else {
-                self.selection_start = -1;
-            }
-
-            if self.selection_start == self.selection_end {
-                self.selection_start = -1;
-            }
+        // Ctrl moves by words, Shift extends the selection
+        if info.input.is_key_pressed(KeyCode::Left) || info.input.is_key_pressed(KeyCode::Right) {
+            let key = match info.input.is_key_pressed(KeyCode::Right) {
+                true => KeyCode::Right,
+                _ => KeyCode::Left
+            };
 
-            if self.caret > 0 {
-                self.caret -= 1;
-            }
-            self.last_keycode = Some(KeyCode::Left);
+            self.step_caret(key == KeyCode::Right, ctrl, shift);
+            self.last_keycode = Some(key);
             self.last_char = None;
             self.last_repeat_count = 0;
             self.key_repeat_timer = self.repeat_delay;
 
-            self.caret = self.caret.clamp(0, self.value.len());
-
             return size;
         }
 
-        // --- immediate press handling ---
-        if is_key_pressed(KeyCode::Right) {
-            if is_key_down(KeyCode::LeftShift) {
-                if self.selection_start < 0 {
-                    self.selection_start = self.caret as i32;
-                    self.selection_end = self.caret as i32 + 1;
-                } else {
-                    self.selection_end = self.caret as i32 + 1;
-                }
-                self.selection_start = self.selection_start.clamp(0, self.value.len() as i32);
-                self.selection_end = self.selection_end.clamp(0, self.value.len() as i32);
-            } else {
-                self.selection_start = -1;
-            }
-
-            if self.selection_start == self.selection_end {
-                self.selection_start = -1;
-            }
-
-            if self.caret < self.value.len() {
-                self.caret += 1;
-            }
-            self.last_keycode = Some(KeyCode::Right);
-            self.last_char = None;
-            self.last_repeat_count = 0;
-            self.key_repeat_timer = self.repeat_delay;
-
-            self.caret = self.caret.clamp(0, self.value.len());
+        if info.input.is_key_pressed(KeyCode::Home) {
+            self.move_caret(0, shift);
+            return size;
+        }
 
+        if info.input.is_key_pressed(KeyCode::End) {
+            self.move_caret(self.value.len(), shift);
             return size;
         }
 
-        if let Some(c) = get_char_pressed() {
-            if (c.is_ascii_alphanumeric() || c.is_ascii_whitespace() || c.is_ascii_punctuation())
-                && c != '\t'
-                && c != '\n'
-            {
-                if !self.value.is_empty() && self.selection_start > -1 {
-                    let start = self.selection_start.min(self.selection_end);
-                    let end = self.selection_start.max(self.selection_end);
-
-                    for _ in start..end {
-                        if self.value.len() > 0 {
-                            self.value.remove(start as usize);
-                        }
+        if !ctrl && !info.input.chars.is_empty() {
+            for &c in info.input.chars.iter() {
+                if self.accepts(c) {
+                    if self.selection_start > -1 {
+                        self.remove_selection();
                     }
 
-                    self.selection_start = -1;
-                    self.selection_end = -1;
-                }
-
-                if self.value.len() == 0 {
-                    self.value.push(c);
-                    self.caret = 1;
-                } else {
                     self.value.insert(self.caret, c);
-                    self.caret += 1;
-                }
+                    self.caret += c.len_utf8();
 
-                self.last_char = Some(c);
-                self.last_keycode = get_last_key_pressed();
-                self.last_repeat_count = 0;
-                self.key_repeat_timer = self.repeat_delay;
-            }
-            
-            if self.value != old_text {
-                self.history.push(old_text.to_string());
+                    self.last_char = Some(c);
+                    self.last_keycode = info.input.last_key_pressed;
+                    self.last_repeat_count = 0;
+                    self.key_repeat_timer = self.repeat_delay;
+                }
             }
+
+            self.record_edit(before, EditKind::Typing, time);
             return size;
         }
 
         // --- repeats with acceleration ---
         if let Some(k) = self.last_keycode {
-            if is_key_down(k) {
+            if info.input.is_key_down(k) {
                 self.key_repeat_timer -= dt;
                 if self.key_repeat_timer <= 0.0 {
                     // perform repeat action
                     match k {
-                        KeyCode::Backspace => {
-                            if !self.value.is_empty() && self.caret > 0 {
-                                self.value.remove(self.caret - 1);
-                                self.caret = (self.caret as i32 - 1).max(0) as usize;
-                            }
-                        }
-                        
-                        KeyCode::Delete => {
-                            if !self.value.is_empty() && self.value.len() > self.caret {
-                                self.value.remove(self.caret);
-                            }
-                        }
-
-                        KeyCode::Left => {
-                            if is_key_down(KeyCode::LeftShift) {
-                                if self.selection_start < 0 {
-                                    self.selection_start = self.caret as i32;
-                                    self.selection_end = self.caret as i32 - 1;
-                                } else {
-                                    self.selection_end = self.caret as i32 - 1;
-                                }
-                                self.selection_start =
-                                    self.selection_start.clamp(0, self.value.len() as i32);
-                                self.selection_end =
-                                    self.selection_end.clamp(0, self.value.len() as i32);
-                            } else {
-                                self.selection_start = -1;
-                            }
-
-                            if self.selection_start == self.selection_end {
-                                self.selection_start = -1;
-                            }
-
-                            if self.caret > 0 {
-                                self.caret -= 1;
-                            }
-                        }
-
-                        KeyCode::Right => {
-                            if is_key_down(KeyCode::LeftShift) {
-                                if self.selection_start < 0 {
-                                    self.selection_start = self.caret as i32;
-                                    self.selection_end = self.caret as i32 + 1;
-                                } else {
-                                    self.selection_end = self.caret as i32 + 1;
-                                }
-                                self.selection_start =
-                                    self.selection_start.clamp(0, self.value.len() as i32);
-                                self.selection_end =
-                                    self.selection_end.clamp(0, self.value.len() as i32);
-                            } else {
-                                self.selection_start = -1;
-                            }
-
-                            if self.selection_start == self.selection_end {
-                                self.selection_start = -1;
-                            }
-
-                            if self.caret < self.value.len() {
-                                self.caret += 1;
-                            }
-                        }
+                        KeyCode::Backspace => self.delete(false, ctrl),
+                        KeyCode::Delete => self.delete(true, ctrl),
+                        KeyCode::Left => self.step_caret(false, ctrl, shift),
+                        KeyCode::Right => self.step_caret(true, ctrl, shift),
 
                         _ => {
                             if let Some(ch) = self.last_char {
-                                if (ch.is_ascii_alphanumeric()
-                                    || ch.is_ascii_whitespace()
-                                    || ch.is_ascii_punctuation())
-                                    && ch != '\t'
-                                    && ch != '\n'
-                                {
-                                    self.value.push(ch);
-                                    self.caret = self.value.len();
+                                if !ch.is_control() && !ctrl {
+                                    self.value.insert(self.caret, ch);
+                                    self.caret += ch.len_utf8();
                                 }
                             }
                         }
@@ -634,24 +662,22 @@ impl Widget for TextBox {
             }
         }
 
-        self.caret = self.caret.clamp(0, self.value.len());
+        self.caret = floor_char_boundary(&self.value, self.caret);
 
         if self.selection_start > -1 {
-            self.selection_start = self.selection_start.clamp(0, self.value.len() as i32);
+            self.selection_start = floor_char_boundary(&self.value, self.selection_start as usize) as i32;
         }
         if self.selection_end > -1 {
-            self.selection_end = self.selection_end.clamp(0, self.value.len() as i32);
+            self.selection_end = floor_char_boundary(&self.value, self.selection_end as usize) as i32;
         }
 
-        if old_caret != self.caret {
-            self.caret_changed = true;
-        } else {
-            self.caret_changed = false;
-        }
+        self.caret_changed = old_caret != self.caret;
         
-        if self.value != old_text {
-            self.history.push(old_text.to_string());
-        }
+        let kind = match self.last_keycode {
+            Some(KeyCode::Backspace | KeyCode::Delete) => EditKind::Deleting,
+            _ => EditKind::Typing,
+        };
+        self.record_edit(before, kind, time);
         
         size
     }
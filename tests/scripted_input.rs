@@ -0,0 +1,244 @@
+use creeperUI::{ActionType, DrawCommand, InputSource, ScriptedInput, WindowTheme, UI};
+use macroquad::miniquad::CursorIcon;
+use macroquad::prelude::*;
+
+/// Plays every queued frame of `input`, declaring the UI with `build` before each one.
+fn run(ui: &mut UI, input: &mut ScriptedInput, mut build: impl FnMut(&mut UI)) {
+    while !input.is_empty() {
+        build(ui);
+        ui.record(&input.poll());
+    }
+}
+
+/// A point just inside the last drawn `text`, to aim the mouse at its widget.
+fn find_text(ui: &UI, text: &str) -> Vec2 {
+    ui.draw_list()
+        .commands()
+        .iter()
+        .find_map(|c| match c {
+            DrawCommand::Text { text: t, position, .. } if t == text => Some(*position + vec2(2.0, -4.0)),
+            _ => None,
+        })
+        .unwrap_or_else(|| panic!("`{text}` wasn't drawn"))
+}
+
+fn form(ui: &mut UI) -> &mut creeperUI::Window {
+    let win = ui.begin("Form");
+    win.set_pos(vec2(100.0, 100.0), ActionType::Once)
+        .set_size(vec2(300.0, 250.0), ActionType::Once);
+    win
+}
+
+fn setup() -> (UI, ScriptedInput) {
+    let mut ui = UI::with_theme(WindowTheme::default());
+    let mut input = ScriptedInput::new(vec2(800.0, 600.0));
+
+    input.idle(2);
+    run(&mut ui, &mut input, |ui| {
+        form(ui).button("OK");
+    });
+
+    (ui, input)
+}
+
+#[test]
+fn clicking_a_button() {
+    let (mut ui, mut input) = setup();
+    let ok = find_text(&ui, "OK");
+
+    let mut clicks = 0;
+    input.click(ok).idle(1);
+    run(&mut ui, &mut input, |ui| {
+        if form(ui).button("OK").clicked {
+            clicks += 1;
+        }
+    });
+
+    assert_eq!(clicks, 1);
+}
+
+#[test]
+fn clicking_next_to_a_button() {
+    let (mut ui, mut input) = setup();
+    let ok = find_text(&ui, "OK");
+
+    let mut clicks = 0;
+    input.click(ok + vec2(150.0, 0.0)).idle(1);
+    run(&mut ui, &mut input, |ui| {
+        if form(ui).button("OK").clicked {
+            clicks += 1;
+        }
+    });
+
+    assert_eq!(clicks, 0);
+}
+
+#[test]
+fn typing_into_a_textbox() {
+    let (mut ui, mut input) = setup();
+    let mut value = String::new();
+
+    input.idle(1);
+    run(&mut ui, &mut input, |ui| {
+        value = form(ui).textbox("name", "Bob").value.clone();
+    });
+    let textbox = find_text(&ui, "Bob");
+
+    // The first click selects everything, so typing replaces it
+    input.click(textbox).type_text("Alice").idle(1);
+    run(&mut ui, &mut input, |ui| {
+        value = form(ui).textbox("name", "Bob").value.clone();
+    });
+
+    assert_eq!(value, "Alice");
+    assert!(ui.wants_text_input());
+    assert_eq!(ui.cursor(), CursorIcon::Text);
+
+    // Enter stops editing, after which typed characters are left alone
+    input.key(KeyCode::Enter).type_text("xyz").idle(1);
+    run(&mut ui, &mut input, |ui| {
+        value = form(ui).textbox("name", "Bob").value.clone();
+    });
+
+    assert_eq!(value, "Alice");
+    assert!(!ui.wants_text_input());
+}
+
+#[test]
+fn selecting_from_a_dropdown() {
+    let (mut ui, mut input) = setup();
+    let items = vec!["Low", "Medium", "High"];
+    let mut value = String::new();
+
+    input.idle(1);
+    run(&mut ui, &mut input, |ui| {
+        value = form(ui).dropdown("quality", items.clone(), "Low").value.clone();
+    });
+    assert_eq!(value, "Low");
+
+    input.click(find_text(&ui, "Low")).idle(1);
+    run(&mut ui, &mut input, |ui| {
+        value = form(ui).dropdown("quality", items.clone(), "Low").value.clone();
+    });
+
+    // The text sits at the bottom of each item's row, so aim a little higher
+    input.click(find_text(&ui, "High") - vec2(0.0, 4.0)).idle(1);
+    run(&mut ui, &mut input, |ui| {
+        value = form(ui).dropdown("quality", items.clone(), "Low").value.clone();
+    });
+
+    assert_eq!(value, "High");
+}
+
+#[test]
+fn dragging_a_window_by_its_titlebar() {
+    let (mut ui, mut input) = setup();
+    let mut position = Vec2::ZERO;
+
+    input
+        .move_mouse(vec2(200.0, 110.0))
+        .mouse_down(MouseButton::Left)
+        .move_mouse(vec2(260.0, 150.0))
+        .move_mouse(vec2(300.0, 190.0))
+        .mouse_up(MouseButton::Left)
+        .idle(1);
+    run(&mut ui, &mut input, |ui| {
+        let win = form(ui);
+        position = win.rect.point();
+        win.button("OK");
+    });
+
+    assert_eq!(position, vec2(200.0, 180.0));
+}
+
+#[test]
+fn dragging_in_the_content_doesnt_move_the_window() {
+    let (mut ui, mut input) = setup();
+    let mut position = Vec2::ZERO;
+
+    input
+        .drag(vec2(200.0, 300.0), vec2(300.0, 400.0))
+        .idle(1);
+    run(&mut ui, &mut input, |ui| {
+        let win = form(ui);
+        position = win.rect.point();
+        win.button("OK");
+    });
+
+    assert_eq!(position, vec2(100.0, 100.0));
+}
+
+#[test]
+fn tabbing_between_rows_with_the_same_labels() {
+    let (mut ui, mut input) = setup();
+    let mut clicks = [0, 0];
+
+    // Each row is its own holder, so both buttons get the same auto ID
+    let mut build = |ui: &mut UI| {
+        let win = form(ui);
+        for (row, clicks) in clicks.iter_mut().enumerate() {
+            win.same_line(format!("row{row}").as_str(), |win| {
+                if win.button("Go").clicked {
+                    *clicks += 1;
+                }
+            });
+        }
+    };
+
+    input.click(vec2(200.0, 300.0)).key(KeyCode::Tab).key(KeyCode::Enter).idle(1);
+    run(&mut ui, &mut input, &mut build);
+    input.key(KeyCode::Tab).key(KeyCode::Enter).idle(1);
+    run(&mut ui, &mut input, &mut build);
+
+    assert_eq!(clicks, [1, 1]);
+}
+
+#[test]
+fn a_checkbox_keeps_its_value_when_its_label_changes() {
+    let (mut ui, mut input) = setup();
+    let mut frame = 0;
+    let mut value = false;
+
+    input.idle(1);
+    run(&mut ui, &mut input, |ui| {
+        form(ui).checkbox("sound", "Sound: off", false);
+    });
+    let checkbox = find_text(&ui, "Sound: off");
+
+    input.click(checkbox).idle(3);
+    run(&mut ui, &mut input, |ui| {
+        frame += 1;
+        value = form(ui).checkbox("sound", format!("Sound ({frame})"), false).value;
+    });
+
+    assert!(value);
+}
+
+#[test]
+fn docks_survive_saving_and_loading() {
+    let layout = "\
+        [Dock][Screen]\n\
+        Split=H,0.25\n\
+        Leaf=1\n\
+        Tab=Tools\n\
+        Tab=Log\n\
+        Central=0\n";
+
+    let mut ui = UI::with_theme(WindowTheme::default());
+    let mut input = ScriptedInput::new(vec2(800.0, 600.0));
+    let mut docked = (false, false);
+    let mut tools = Rect::default();
+
+    ui.load_layout(layout);
+    input.idle(3);
+    run(&mut ui, &mut input, |ui| {
+        let win = ui.begin("Tools");
+        docked.0 = win.is_docked();
+        tools = win.rect;
+        docked.1 = ui.begin("Log").is_docked();
+    });
+
+    assert_eq!(docked, (true, true));
+    assert_eq!(tools, Rect::new(0.0, 0.0, 200.0, 600.0));
+    assert!(ui.save_layout().contains(layout));
+}